@@ -0,0 +1,195 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use cubelib::algs::Algorithm;
+use cubelib::defs::NissSwitchType;
+use cubelib::puzzles::c333::{Cube333, Turn333};
+use cubelib::solver::solution::{Solution, SolutionStep};
+use cubelib::steps::pipeline::Pipeline;
+use cubelib::steps::step::StepConfig;
+use log::error;
+use moka::sync::Cache;
+
+use crate::db;
+
+//Completed solution streams by cube state and step settings. A scramble and its inverse share an entry if the settings
+//treat both sides the same, because every solution of a cube turns into a solution of its inverse by swapping the normal and inverse moves
+pub struct SolutionCache {
+    memory: Cache<String, Arc<CachedSolutions>>,
+    persist: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CachedSolutions {
+    //Whether the solutions were computed for the inverse of the canonical cube
+    inverted: bool,
+    solutions: Vec<Solution<Turn333>>,
+    //How long the original request took to find the best solution. Missing in entries persisted by older versions
+    #[serde(default)]
+    best_found_ms: Option<u64>,
+}
+
+pub struct CacheHit {
+    pub solutions: Vec<Solution<Turn333>>,
+    pub best_found: Option<Duration>,
+}
+
+pub struct CacheKey {
+    key: String,
+    inverted: bool,
+}
+
+impl CacheKey {
+    pub fn new(cube: &Cube333, steps: &[StepConfig]) -> CacheKey {
+        let (cube_id, inverted) = db::get_canonical_cube_id(cube);
+        //Inverting a solution moves every step to the other side. That only finds the same solutions if every step can use NISS
+        //and no parameter (e.g. DR triggers or HTR subsets) only applies to one side
+        let shareable = steps.iter().all(|step| step.niss == Some(NissSwitchType::Always) && step.params.is_empty());
+        //The solver overrides these, so they don't change the result
+        let steps: Vec<StepConfig> = steps.iter()
+            .cloned()
+            .map(|mut step| {
+                step.quality = 0;
+                step.step_limit = None;
+                step
+            })
+            .collect();
+        //The canonical form sorts the params, so the key doesn't depend on the order of the HashMap
+        let pipeline = Pipeline::new(steps).to_string();
+        let key = if shareable {
+            format!("{cube_id}:{pipeline}")
+        } else {
+            format!("{cube_id}:{}:{pipeline}", if inverted { "inverse" } else { "normal" })
+        };
+        CacheKey {
+            key,
+            inverted,
+        }
+    }
+}
+
+impl SolutionCache {
+    pub fn new(max_entries: u64, persist: bool) -> SolutionCache {
+        SolutionCache {
+            memory: Cache::new(max_entries),
+            persist,
+        }
+    }
+
+    pub fn get(&self, key: &CacheKey, conn: &db::Connection) -> Option<CacheHit> {
+        let cached = match self.memory.get(&key.key) {
+            Some(cached) => cached,
+            None if self.persist => {
+                let cached = match db::load_cached_solutions(conn, key.key.as_str()) {
+                    Ok(cached) => cached?,
+                    Err(err) => {
+                        error!("{err}");
+                        return None;
+                    }
+                };
+                let cached: Arc<CachedSolutions> = Arc::new(serde_json::from_str(cached.as_str()).ok()?);
+                self.memory.insert(key.key.clone(), cached.clone());
+                cached
+            },
+            None => return None,
+        };
+        let solutions = if cached.inverted == key.inverted {
+            cached.solutions.clone()
+        } else {
+            cached.solutions.iter().map(invert_solution).collect()
+        };
+        Some(CacheHit {
+            solutions,
+            best_found: cached.best_found_ms.map(Duration::from_millis),
+        })
+    }
+
+    //Empty results are never cached. They come from failed or interrupted solves and would hide the solutions of a later request
+    pub fn insert(&self, key: &CacheKey, solutions: Vec<Solution<Turn333>>, best_found: Option<Duration>, conn: &db::Connection) {
+        if solutions.is_empty() {
+            return;
+        }
+        let cached = Arc::new(CachedSolutions {
+            inverted: key.inverted,
+            solutions,
+            best_found_ms: best_found.map(|found| found.as_millis() as u64),
+        });
+        if self.persist {
+            if let Err(err) = db::store_cached_solutions(conn, key.key.as_str(), serde_json::to_string(cached.as_ref()).unwrap().as_str()) {
+                error!("{err}");
+            }
+        }
+        self.memory.insert(key.key.clone(), cached);
+    }
+}
+
+//Turns a solution of a cube into a solution of the inverse cube. Every step still reaches the same subgroup,
+//because the inverse of a cube in EO, DR, HTR or FR is in the same subgroup on the same axis
+fn invert_solution(solution: &Solution<Turn333>) -> Solution<Turn333> {
+    let mut inverted = Solution::new();
+    for step in solution.steps.iter() {
+        inverted.add_step(SolutionStep {
+            kind: step.kind.clone(),
+            variant: step.variant.clone(),
+            alg: Algorithm {
+                normal_moves: step.alg.inverse_moves.clone(),
+                inverse_moves: step.alg.normal_moves.clone(),
+            },
+        });
+    }
+    inverted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use cubelib::puzzles::puzzle::{ApplyAlgorithm, InvertibleMut};
+
+    use super::*;
+
+    fn cube(scramble: &str) -> Cube333 {
+        let mut cube = Cube333::default();
+        cube.apply_alg(&Algorithm::from_str(scramble).unwrap());
+        cube
+    }
+
+    fn steps(pipeline: &str) -> Vec<StepConfig> {
+        Pipeline::from_str(pipeline).unwrap().steps
+    }
+
+    #[test]
+    fn key_does_not_depend_on_param_order() {
+        let mut a = steps("EO > DR");
+        let mut b = a.clone();
+        let params = [("triggers", "R,RU2R"), ("subsets", "4a1"), ("rzp", "2"), ("qt", "1")];
+        a[1].params = params.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        b[1].params = params.iter().rev().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<String, String>>();
+        let cube = cube("R U F");
+        assert_eq!(CacheKey::new(&cube, &a).key, CacheKey::new(&cube, &b).key);
+    }
+
+    #[test]
+    fn key_ignores_quality_and_limit() {
+        let cube = cube("R U F");
+        let mut a = steps("EO[limit=5] > DR");
+        a[0].quality = 100;
+        assert_eq!(CacheKey::new(&cube, &a).key, CacheKey::new(&cube, &steps("EO > DR")).key);
+    }
+
+    #[test]
+    fn inverse_shares_key_only_with_niss_always() {
+        let normal = cube("R U F D2 L'");
+        let mut inverse = normal;
+        inverse.invert();
+
+        let always = steps("EO[niss=always] > DR[niss=always]");
+        assert_eq!(CacheKey::new(&normal, &always).key, CacheKey::new(&inverse, &always).key);
+
+        for pipeline in ["EO[niss=always] > DR[niss=before]", "EO[niss=always] > DR", "EO[niss=always] > DR[niss=always;triggers=R]"] {
+            let steps = steps(pipeline);
+            assert_ne!(CacheKey::new(&normal, &steps).key, CacheKey::new(&inverse, &steps).key, "{pipeline}");
+        }
+    }
+}
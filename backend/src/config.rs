@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::Parser;
+use cubelib::defs::StepKind;
+use serde::Deserialize;
+
+//Every setting can be passed as an argument, an environment variable or in the config file, in that order of precedence
+#[derive(Parser, Debug)]
+#[command(version, about = "Cubelib solver backend")]
+struct Args {
+    #[arg(long, env = "CUBELIB_CONFIG", help = "TOML file with any of the settings below, using the argument names with underscores, e.g. max_quality = 4096")]
+    config: Option<PathBuf>,
+    #[command(flatten)]
+    settings: Settings,
+}
+
+#[derive(clap::Args, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct Settings {
+    #[arg(long, env = "CUBELIB_BIND", help = "Address and port to listen on [default: 127.0.0.1:8049]")]
+    bind: Option<String>,
+    #[arg(long, env = "CUBELIB_DB", help = "SQLite database for requests and cached solutions [default: requests.db]")]
+    db: Option<PathBuf>,
+    #[arg(long, env = "CUBELIB_TABLE_DIR", help = "Directory to load and store pruning tables in [default: ~/.cubelib/tables/333]")]
+    table_dir: Option<PathBuf>,
+    #[arg(long, env = "CUBELIB_TABLES", value_delimiter = ',', help = "Tables to load at startup, all others are loaded on the first request that needs them [default: all]")]
+    tables: Option<Vec<String>>,
+    #[arg(long, env = "CUBELIB_STEPS", value_delimiter = ',', help = "Step kinds that can be requested, e.g. eo,rzp,dr,htr [default: all]")]
+    steps: Option<Vec<String>>,
+    #[arg(long, env = "CUBELIB_SOLVE_TIMEOUT_SECS", help = "Solves are stopped and the best solution so far is kept after this many seconds [default: 60]")]
+    solve_timeout_secs: Option<u64>,
+    #[arg(long, env = "CUBELIB_DEBOUNCE_MS", help = "Identical solve requests from the same IP within this time are rejected [default: 500]")]
+    debounce_ms: Option<u64>,
+    #[arg(long, env = "CUBELIB_MAX_QUALITY", help = "Solves start at quality 32 and double until this quality is reached [default: 524288]")]
+    max_quality: Option<usize>,
+    #[arg(long, env = "CUBELIB_MAX_SOLVES", help = "Number of solves that run at the same time [default: number of cores]")]
+    max_solves: Option<usize>,
+    #[arg(long, env = "CUBELIB_MAX_QUEUED_SOLVES", help = "Number of solve requests that wait for a free slot before new requests are rejected [default: 32]")]
+    max_queued_solves: Option<usize>,
+    #[arg(long, env = "CUBELIB_SOLUTION_CACHE_SIZE", help = "Number of completed solves kept in memory [default: 10000]")]
+    solution_cache_size: Option<u64>,
+    #[arg(long, env = "CUBELIB_SOLUTION_CACHE_SQLITE", value_parser = clap::builder::BoolishValueParser::new(), help = "Also store completed solves in the database, so they survive restarts [default: false]")]
+    solution_cache_sqlite: Option<bool>,
+}
+
+pub struct Config {
+    pub bind: String,
+    pub db: PathBuf,
+    pub table_dir: Option<PathBuf>,
+    pub tables: Option<Vec<String>>,
+    pub steps: Option<Vec<StepKind>>,
+    pub solve_timeout: Duration,
+    pub debounce: Duration,
+    pub max_quality: usize,
+    pub max_solves: usize,
+    pub max_queued_solves: usize,
+    pub solution_cache_size: u64,
+    pub solution_cache_sqlite: bool,
+}
+
+impl Config {
+    pub fn load() -> Result<Config, String> {
+        let args = Args::parse();
+        let file = match args.config {
+            Some(path) => {
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Could not read config file {}: {e}", path.display()))?;
+                toml::from_str(content.as_str())
+                    .map_err(|e| format!("Invalid config file {}: {e}", path.display()))?
+            },
+            None => Settings::default(),
+        };
+        Config::from_settings(args.settings, file)
+    }
+
+    fn from_settings(args: Settings, file: Settings) -> Result<Config, String> {
+        let steps = match args.steps.or(file.steps) {
+            Some(steps) => Some(steps.iter().map(|s| parse_step_kind(s.as_str())).collect::<Result<Vec<StepKind>, String>>()?),
+            None => None,
+        };
+        Ok(Config {
+            bind: args.bind.or(file.bind).unwrap_or_else(|| "127.0.0.1:8049".to_string()),
+            db: args.db.or(file.db).unwrap_or_else(|| PathBuf::from("requests.db")),
+            table_dir: args.table_dir.or(file.table_dir),
+            tables: args.tables.or(file.tables),
+            steps,
+            solve_timeout: Duration::from_secs(args.solve_timeout_secs.or(file.solve_timeout_secs).unwrap_or(60)),
+            debounce: Duration::from_millis(args.debounce_ms.or(file.debounce_ms).unwrap_or(500)),
+            max_quality: args.max_quality.or(file.max_quality).unwrap_or(1 << 19),
+            max_solves: args.max_solves.or(file.max_solves)
+                .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)),
+            max_queued_solves: args.max_queued_solves.or(file.max_queued_solves).unwrap_or(32),
+            solution_cache_size: args.solution_cache_size.or(file.solution_cache_size).unwrap_or(10000),
+            solution_cache_sqlite: args.solution_cache_sqlite.or(file.solution_cache_sqlite).unwrap_or(false),
+        })
+    }
+
+    pub fn is_step_enabled(&self, kind: &StepKind) -> bool {
+        self.steps.as_ref().map_or(true, |steps| steps.contains(kind))
+    }
+}
+
+//StepKind::from_str accepts any name as a custom step kind, which the server can't solve
+fn parse_step_kind(name: &str) -> Result<StepKind, String> {
+    match StepKind::from_str(name.trim())? {
+        StepKind::Other(name) => Err(format!("Unknown step kind '{name}' in steps. Valid kinds are eo, rzp, dr, htr, fr, frls and finish")),
+        kind => Ok(kind),
+    }
+}
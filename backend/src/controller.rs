@@ -2,26 +2,37 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 use actix_web::{HttpResponse, post, get, Responder, web, HttpRequest};
+use actix_web::error::BlockingError;
+use actix_web::http::header;
 use actix_web::web::Bytes;
 use actix_web_lab::body;
 use base64::Engine;
 use cubelib::algs::Algorithm;
 use cubelib::puzzles::c333::{Cube333, Turn333};
-use cubelib::puzzles::c333::steps::solver;
+use cubelib::puzzles::c333::steps::{analysis, solver};
+use cubelib::puzzles::c333::steps::verify as verification;
 use cubelib::puzzles::c333::steps::tables::PruningTables333;
 use cubelib::puzzles::puzzle::ApplyAlgorithm;
 use cubelib::solver::CancellationToken;
 use cubelib::solver::solution::Solution;
 use cubelib::steps::step::StepConfig;
-use cubelib_interface::{SolverRequest, SolverResponse};
+use cubelib_interface::{AnalyzeRequest, AnalyzeResponse, HistoryQuery, HistoryResponse, PageQuery, SolverError, SolverRequest, SolverResponse, SolverUpdate, VerifyRequest, VerifyResponse};
 use log::{debug, error, info, trace};
+use serde::Serialize;
 use crate::{AppData, db};
+use crate::cache::CacheKey;
+
+pub const QUEUE_FULL_RETRY_AFTER_SECS: u64 = 10;
+const SOLVE_STREAM: &str = "solve_stream";
 
 #[post("/solve_stream")]
 pub async fn solve_stream(req: HttpRequest, steps: web::Json<SolverRequest>, app_data: web::Data<AppData>) -> impl Responder {
+    let received = Instant::now();
+    let metrics = app_data.metrics.clone();
     let mut hasher = DefaultHasher::new();
     let peer = req.peer_addr().unwrap();
     peer.ip().to_string().hash(&mut hasher);
@@ -30,45 +41,150 @@ pub async fn solve_stream(req: HttpRequest, steps: web::Json<SolverRequest>, app
     let last = app_data.debounce_cache.get_with(hash, ||peer);
     if last.ip() == peer.ip() && last.port() != peer.port() {
         debug!("Debounce kill");
+        metrics.debounce_kill();
+        metrics.request(SOLVE_STREAM, "debounced");
         return HttpResponse::ServiceUnavailable().finish()
     }
 
     let SolverRequest{ steps, scramble } = steps.0;
-    let scramble = Algorithm::from_str(scramble.as_str()).unwrap();
-    let conn = app_data.pool.get();
-
-    match conn {
-        Ok(conn) => {
-            if let Err(err) = db::record_request(&conn, &scramble, &steps, app_data.pruning_tables.as_ref()) {
-                error!("{err}");
-                return HttpResponse::InternalServerError().finish()
-            }
+    let scramble = match Algorithm::from_str(scramble.as_str()) {
+        Ok(scramble) => scramble,
+        Err(_) => {
+            metrics.request(SOLVE_STREAM, "bad_request");
+            return HttpResponse::BadRequest().body(error_lines(SolverError::InvalidScramble { scramble }))
+        }
+    };
+    let validation = {
+        let steps = steps.clone();
+        let app_data = app_data.clone();
+        web::block(move || validate_steps(&steps, &app_data)).await
+    };
+    match validation {
+        Ok(Ok(())) => {},
+        Ok(Err(message)) => {
+            metrics.request(SOLVE_STREAM, "bad_request");
+            return HttpResponse::BadRequest().body(error_lines(SolverError::InvalidStepConfig { message }))
         },
         Err(err) => {
             error!("{err}");
-            return HttpResponse::InternalServerError().finish()
+            metrics.request(SOLVE_STREAM, "error");
+            return HttpResponse::InternalServerError().body(error_lines(SolverError::Internal))
         }
     }
-
-    info!("Streaming solve request for {scramble}");
+    let conn = match app_data.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            error!("{err}");
+            metrics.request(SOLVE_STREAM, "error");
+            return HttpResponse::InternalServerError().body(error_lines(SolverError::Internal))
+        }
+    };
+    let request_id = match db::record_request(&conn, &scramble, &steps, app_data.pruning_tables.as_ref()) {
+        Ok(id) => id,
+        Err(err) => {
+            error!("{err}");
+            metrics.request(SOLVE_STREAM, "error");
+            return HttpResponse::InternalServerError().body(error_lines(SolverError::Internal))
+        }
+    };
 
     let mut cube = Cube333::default();
     cube.apply_alg(&scramble);
 
-    let cancel_token = CancellationToken::new();
-    let cancel_token_1 = cancel_token.clone();
-    let mut solutions = solve_steps_quality_doubling(cube, steps.clone(), app_data.pruning_tables.clone(), cancel_token.clone())
-        .map(|s| Some(SolverResponse { solution: Some(s), done: false }));
+    let cache_key = CacheKey::new(&cube, &steps);
+    if let Some(cached) = app_data.solution_cache.get(&cache_key, &conn) {
+        info!("Returning cached solutions for {scramble}");
+        metrics.request(SOLVE_STREAM, "cached");
+        for _ in cached.solutions.iter() {
+            metrics.solution_streamed();
+        }
+        //Recorded with the time of the request that found the solutions, not the time of the lookup
+        if let (Some(best), Some(found)) = (cached.solutions.last(), cached.best_found) {
+            if let Err(err) = db::record_solution(&conn, request_id, &steps, best, found) {
+                error!("{err}");
+            }
+        }
+        let body = cached.solutions.into_iter()
+            .map(|solution| SolverUpdate::Solution { solution })
+            .chain(std::iter::once(SolverUpdate::Done { cancelled: false }))
+            .map(response_line)
+            .collect::<String>();
+        return HttpResponse::Ok().body(body)
+    }
+    drop(conn);
 
+    let ticket = match app_data.job_queue.enqueue() {
+        Some(ticket) => ticket,
+        None => {
+            debug!("Solver queue full");
+            metrics.request(SOLVE_STREAM, "queue_full");
+            return HttpResponse::ServiceUnavailable()
+                .insert_header((header::RETRY_AFTER, QUEUE_FULL_RETRY_AFTER_SECS.to_string()))
+                .body(error_lines(SolverError::Overloaded { retry_after_secs: QUEUE_FULL_RETRY_AFTER_SECS }))
+        }
+    };
+
+    info!("Streaming solve request for {scramble}");
 
     let (mut body_tx, body) = body::channel::<std::convert::Infallible>();
 
+    let cancel_token = CancellationToken::new();
+    let mut progress_tx = body_tx.clone();
+    let progress_token = cancel_token.clone();
+    let solutions = solve_steps_quality_doubling(cube, steps.clone(), app_data.config.max_quality, app_data.pruning_tables.clone(), cancel_token.clone(), move |quality| {
+        if progress_token.is_cancelled() {
+            return;
+        }
+        let elapsed_ms = received.elapsed().as_millis() as u64;
+        let _ = progress_tx.send(web::Bytes::from(response_line(SolverUpdate::Progress { queue_position: None, quality: Some(quality), elapsed_ms })));
+    });
+    let solve_timeout = app_data.config.solve_timeout;
+    let solution_cache = app_data.solution_cache.clone();
+    let pool = app_data.pool.clone();
+    let timed_out = Arc::new(AtomicBool::new(false));
+
     let _ = web::block(move || {
+        let mut ticket = ticket;
+        let mut last_position = None;
+        //Report the position right away if there is no free slot, afterwards it doubles as keepalive
+        let mut timeout = Duration::ZERO;
+        let _slot = loop {
+            match ticket.wait(timeout) {
+                Ok(slot) => break slot,
+                Err((t, position)) => {
+                    ticket = t;
+                    timeout = Duration::from_secs(1);
+                    let data = if last_position == Some(position) {
+                        web::Bytes::from_static(b" ")
+                    } else {
+                        last_position = Some(position);
+                        let elapsed_ms = received.elapsed().as_millis() as u64;
+                        web::Bytes::from(response_line(SolverUpdate::Progress { queue_position: Some(position), quality: None, elapsed_ms }))
+                    };
+                    if let Err(_) = body_tx.send(data) {
+                        info!("Stream closed while queued");
+                        metrics.request(SOLVE_STREAM, "disconnected");
+                        metrics.cancellation("disconnected");
+                        return;
+                    }
+                }
+            }
+        };
+
         let mut keepalive_tx = body_tx.clone();
         let cancel_token_1 = cancel_token.clone();
+        let metrics_1 = metrics.clone();
+        let timed_out_1 = timed_out.clone();
         let _ = web::block(move || {
+            let deadline = Instant::now() + solve_timeout;
             while !cancel_token_1.is_cancelled() {
                 sleep(Duration::from_secs(1));
+                if Instant::now() > deadline {
+                    metrics_1.cancellation("timeout");
+                    timed_out_1.store(true, Ordering::Relaxed);
+                    cancel_token_1.cancel();
+                    break;
+                }
                 trace!("Sending keepalive");
                 if let Err(_) = keepalive_tx.send(web::Bytes::from_static(b" ")) {
                     info!("Stream closed, cancelling");
@@ -76,46 +192,223 @@ pub async fn solve_stream(req: HttpRequest, steps: web::Json<SolverRequest>, app
                 }
             }
         });
+        let mut sent = vec![];
+        let mut best_found = None;
         for sol in solutions {
             if cancel_token.is_cancelled() {
                 break;
             }
-            let data = web::Bytes::from(serde_json::to_string(&sol).unwrap());
+            let data = web::Bytes::from(response_line(SolverUpdate::Solution { solution: sol.clone() }));
             if let Err(_) = body_tx.send(data) {
                 break;
             }
-            if let Err(_) = body_tx.send(web::Bytes::from_static(b"\n")) {
-                break;
+            if sent.is_empty() {
+                metrics.first_solution(received.elapsed());
             }
+            best_found = Some(received.elapsed());
+            metrics.solution_streamed();
+            sent.push(sol);
         }
+        //The solver stops early when the token is cancelled, e.g. by the timeout. Those results aren't the best ones it can find
+        let finished = !cancel_token.is_cancelled();
         cancel_token.cancel();
-        let data = web::Bytes::from(serde_json::to_string(&SolverResponse { solution: None, done: true }).unwrap());
-        let _ = body_tx.send(data);
-        let _ = body_tx.send(web::Bytes::from_static(b"\n"));
-    });
-
-    actix_rt::spawn(async move {
-        actix_rt::time::sleep(Duration::from_secs(60)).await;
-        cancel_token_1.cancel();
+        //The best solution is recorded even if the client is gone
+        let conn = match pool.get() {
+            Ok(conn) => Some(conn),
+            Err(err) => {
+                error!("{err}");
+                None
+            }
+        };
+        if let (Some(conn), Some(best), Some(found)) = (&conn, sent.last(), best_found) {
+            if let Err(err) = db::record_solution(conn, request_id, &steps, best, found) {
+                error!("{err}");
+            }
+        }
+        let data = if sent.is_empty() && timed_out.load(Ordering::Relaxed) {
+            web::Bytes::from(error_lines(SolverError::Timeout))
+        } else {
+            web::Bytes::from(response_line(SolverUpdate::Done { cancelled: false }))
+        };
+        //Streams that were closed early are missing solutions, so only the ones that reached the end without a timeout are cached
+        if body_tx.send(data).is_err() {
+            metrics.request(SOLVE_STREAM, "disconnected");
+            metrics.cancellation("disconnected");
+            return;
+        }
+        metrics.request(SOLVE_STREAM, "streamed");
+        if let Some(found) = best_found {
+            metrics.best_solution(found);
+        }
+        if !finished {
+            return;
+        }
+        if let Some(conn) = conn {
+            solution_cache.insert(&cache_key, sent, best_found, &conn);
+        }
     });
 
     HttpResponse::Ok().body(body)
 }
 
-pub fn solve_steps_quality_doubling<'a>(puzzle: Cube333, steps: Vec<StepConfig>, tables: Arc<PruningTables333>, cancel_token: CancellationToken) -> impl Iterator<Item = Solution<Turn333>> {
+//Checks that the steps are enabled and that they can be built, which fails e.g. for steps in an unsupported order.
+//Building the steps loads or generates tables that weren't preloaded, so this has to run on a blocking thread
+pub fn validate_steps(steps: &[StepConfig], app_data: &AppData) -> Result<(), String> {
+    if let Some(step) = steps.iter().find(|step| !app_data.config.is_step_enabled(&step.kind)) {
+        return Err(format!("Step {} is not enabled on this server", step.kind));
+    }
+    solver::build_steps(steps.to_vec(), app_data.pruning_tables.as_ref()).map(|_| ())
+}
+
+fn response_line(update: SolverUpdate) -> String {
+    serde_json::to_string(&SolverResponse::new(update)).unwrap() + "\n"
+}
+
+//An error is always followed by done, so clients can handle failed requests like any other stream
+fn error_lines(error: SolverError) -> String {
+    response_line(SolverUpdate::Error { error }) + &response_line(SolverUpdate::Done { cancelled: false })
+}
+
+#[post("/analyze")]
+pub async fn analyze(request: web::Json<AnalyzeRequest>, app_data: web::Data<AppData>) -> impl Responder {
+    let cube = match request.get_cube() {
+        Ok(cube) => cube,
+        Err(err) => {
+            app_data.metrics.request("analyze", "bad_request");
+            return HttpResponse::BadRequest().body(err)
+        },
+    };
+    let tables = app_data.pruning_tables.clone();
+    match web::block(move || analysis::analyze(&cube, tables.as_ref())).await {
+        Ok(analysis) => {
+            app_data.metrics.request("analyze", "ok");
+            HttpResponse::Ok().json(AnalyzeResponse { analysis })
+        },
+        Err(err) => {
+            error!("{err}");
+            app_data.metrics.request("analyze", "error");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[post("/verify")]
+pub async fn verify(request: web::Json<VerifyRequest>, app_data: web::Data<AppData>) -> impl Responder {
+    let VerifyRequest { scramble, solution } = request.0;
+    let scramble = match Algorithm::from_str(scramble.as_str()) {
+        Ok(scramble) => scramble,
+        Err(_) => {
+            app_data.metrics.request("verify", "bad_request");
+            return HttpResponse::BadRequest().body(format!("Invalid scramble {scramble}"))
+        },
+    };
+    let solution = match verification::parse_solution(solution.as_str()) {
+        Ok(solution) => solution,
+        Err(err) => {
+            app_data.metrics.request("verify", "bad_request");
+            return HttpResponse::BadRequest().body(err)
+        },
+    };
+    app_data.metrics.request("verify", "ok");
+    HttpResponse::Ok().json(VerifyResponse { verification: verification::verify(&scramble, &solution) })
+}
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[get("/history")]
+pub async fn history(query: web::Query<HistoryQuery>, app_data: web::Data<AppData>) -> impl Responder {
+    let scramble = match Algorithm::<Turn333>::from_str(query.scramble.as_str()) {
+        Ok(scramble) => scramble,
+        Err(_) => {
+            app_data.metrics.request("history", "bad_request");
+            return HttpResponse::BadRequest().body(format!("Invalid scramble {}", query.scramble))
+        },
+    };
+    let canonical_scramble_id = db::get_canonical_scramble_id(&scramble);
+    let pool = app_data.pool.clone();
+    let result = web::block(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        let requests = db::request_history(&conn, &canonical_scramble_id).map_err(|e| e.to_string())?;
+        Ok::<_, String>(HistoryResponse { canonical_scramble_id, requests })
+    }).await;
+    respond("history", result, &app_data)
+}
+
+#[get("/best_solutions")]
+pub async fn best_solutions(query: web::Query<PageQuery>, app_data: web::Data<AppData>) -> impl Responder {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0);
+    let pool = app_data.pool.clone();
+    let result = web::block(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        db::best_solutions(&conn, limit, offset).map_err(|e| e.to_string())
+    }).await;
+    respond("best_solutions", result, &app_data)
+}
+
+//The limit applies to the most requested scrambles
+#[get("/stats")]
+pub async fn stats(query: web::Query<PageQuery>, app_data: web::Data<AppData>) -> impl Responder {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let pool = app_data.pool.clone();
+    let result = web::block(move || {
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        db::stats(&conn, limit).map_err(|e| e.to_string())
+    }).await;
+    respond("stats", result, &app_data)
+}
+
+fn respond<T: Serialize>(endpoint: &'static str, result: Result<Result<T, String>, BlockingError>, app_data: &AppData) -> HttpResponse {
+    match result {
+        Ok(Ok(value)) => {
+            app_data.metrics.request(endpoint, "ok");
+            HttpResponse::Ok().json(value)
+        },
+        Ok(Err(err)) => {
+            error!("{err}");
+            app_data.metrics.request(endpoint, "error");
+            HttpResponse::InternalServerError().finish()
+        },
+        Err(err) => {
+            error!("{err}");
+            app_data.metrics.request(endpoint, "error");
+            HttpResponse::InternalServerError().finish()
+        },
+    }
+}
+
+#[get("/metrics")]
+pub async fn prometheus_metrics(app_data: web::Data<AppData>) -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(app_data.metrics.render(app_data.job_queue.as_ref()))
+}
+
+//Solves with increasing quality and returns every solution that is shorter than the previous one. on_quality is called before every search
+pub fn solve_steps_quality_doubling<F: FnMut(usize)>(puzzle: Cube333, steps: Vec<StepConfig>, max_quality: usize, tables: Arc<PruningTables333>, cancel_token: CancellationToken, mut on_quality: F) -> impl Iterator<Item = Solution<Turn333>> {
     let mut prev_len: Option<usize> = None;
-    (5..20usize).into_iter()
-        .map(|q| 2u32.pow(q as u32) as usize)
+    (5..usize::BITS)
+        .map(|q| 1usize << q)
+        .take_while(move |quality| *quality <= max_quality)
         .flat_map(move |quality| {
+            on_quality(quality);
             let mut steps = steps.clone();
             for x in &mut steps {
                 x.quality = quality;
                 x.step_limit = None;
             }
-            let tables = tables.as_ref().clone();
-            let steps = solver::build_steps(steps, &tables).unwrap();
-            let best = cubelib::solver::solve_steps(puzzle, &steps, cancel_token.clone()).next();
-            best
+            //The steps were validated before the solve, so this only fails if a quality can't be used
+            match solver::build_steps(steps, tables.as_ref()) {
+                Ok(steps) => {
+                    let best = cubelib::solver::solve_steps(puzzle, &steps, cancel_token.clone()).next();
+                    best
+                },
+                Err(err) => {
+                    error!("{err}");
+                    None
+                },
+            }
         })
         .filter(move |sol| {
             match prev_len {
@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use base64::Engine;
 
 use cubelib::algs::Algorithm;
@@ -8,11 +8,16 @@ use cubelib::puzzles::c333::steps::solver::build_steps;
 use cubelib::puzzles::c333::steps::tables::PruningTables333;
 use cubelib::puzzles::puzzle::{ApplyAlgorithm, InvertibleMut};
 use cubelib::solver::{CancellationToken, solve_steps};
+use cubelib::solver::solution::Solution;
 use cubelib::steps::step::StepConfig;
+use cubelib_interface::{BestSolution, HistoryEntry, PipelineStats, RecordedSolution, ScrambleCount, StatsResponse};
+use rusqlite::OptionalExtension;
+
+use crate::migrations;
 
 pub type Connection = r2d2::PooledConnection<r2d2_sqlite::SqliteConnectionManager>;
 
-pub fn record_request(conn: &Connection, scramble: &Algorithm<Turn333>, step_configs: &Vec<StepConfig>, tables: &PruningTables333) -> rusqlite::Result<()> {
+pub fn record_request(conn: &Connection, scramble: &Algorithm<Turn333>, step_configs: &Vec<StepConfig>, tables: &PruningTables333) -> rusqlite::Result<i64> {
     let time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).expect("System time before unix epoch").as_secs();
     let canonical = get_canonical_scramble_id(scramble);
     let encoded_steps = base64::engine::general_purpose::STANDARD.encode(serde_json::to_string(step_configs).unwrap());
@@ -24,35 +29,168 @@ pub fn record_request(conn: &Connection, scramble: &Algorithm<Turn333>, step_con
             canonical,
             encoded_steps
         )
+    ).map(|_| conn.last_insert_rowid())
+}
+
+//The step kinds of a request, e.g. "eo > dr > htr > finish"
+pub fn pipeline_name(step_configs: &[StepConfig]) -> String {
+    step_configs.iter()
+        .map(|step| step.kind.to_string())
+        .collect::<Vec<String>>()
+        .join(" > ")
+}
+
+pub fn record_solution(conn: &Connection, request_id: i64, step_configs: &[StepConfig], solution: &Solution<Turn333>, time: Duration) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO solutions (id, request_id, pipeline, length, solution, time_ms) VALUES (NULL, ?, ?, ?, ?, ?)",
+        (
+            request_id,
+            pipeline_name(step_configs),
+            solution.len(),
+            serde_json::to_string(solution).unwrap(),
+            time.as_millis() as u64,
+        )
     ).map(|_|())
 }
 
-fn get_canonical_scramble_id(scramble: &Algorithm<Turn333>) -> String {
+pub fn request_history(conn: &Connection, canonical_id: &str) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let mut stmt = conn.prepare("
+        SELECT r.id, r.timestamp, r.scramble, r.settings, s.pipeline, s.length, s.solution, s.time_ms
+        FROM requests r LEFT JOIN solutions s ON s.request_id = r.id
+        WHERE r.canonical_scramble_id = ?
+        ORDER BY r.id DESC
+    ")?;
+    let rows = stmt.query_map([canonical_id], |row| {
+        let settings: String = row.get(3)?;
+        let best = match row.get::<_, Option<String>>(6)? {
+            Some(solution) => Some(recorded_solution(row.get(4)?, row.get(5)?, solution, row.get(7)?)?),
+            None => None,
+        };
+        Ok(HistoryEntry {
+            request_id: row.get(0)?,
+            timestamp: row.get(1)?,
+            scramble: row.get(2)?,
+            steps: decode_settings(settings.as_str()).unwrap_or_default(),
+            best,
+        })
+    })?;
+    rows.collect()
+}
+
+//The shortest solution of every scramble, ties go to the one found first. Scrambles with the most recent solutions come first
+pub fn best_solutions(conn: &Connection, limit: usize, offset: usize) -> rusqlite::Result<Vec<BestSolution>> {
+    let mut stmt = conn.prepare("
+        SELECT canonical_scramble_id, scramble, pipeline, length, solution, time_ms
+        FROM (
+            SELECT r.canonical_scramble_id, r.scramble, s.pipeline, s.length, s.solution, s.time_ms,
+                ROW_NUMBER() OVER (PARTITION BY r.canonical_scramble_id ORDER BY s.length, s.id) AS rank,
+                MAX(s.id) OVER (PARTITION BY r.canonical_scramble_id) AS latest
+            FROM solutions s JOIN requests r ON r.id = s.request_id
+        )
+        WHERE rank = 1
+        ORDER BY latest DESC
+        LIMIT ? OFFSET ?
+    ")?;
+    let rows = stmt.query_map([limit, offset], |row| {
+        Ok(BestSolution {
+            canonical_scramble_id: row.get(0)?,
+            scramble: row.get(1)?,
+            best: recorded_solution(row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub fn stats(conn: &Connection, limit: usize) -> rusqlite::Result<StatsResponse> {
+    let total_requests = conn.query_row("SELECT COUNT(*) FROM requests", [], |row| row.get(0))?;
+    let total_solutions = conn.query_row("SELECT COUNT(*) FROM solutions", [], |row| row.get(0))?;
+    //The scramble is taken from the row with the highest id, i.e. the most recent request
+    let mut stmt = conn.prepare("
+        SELECT canonical_scramble_id, scramble, MAX(id), COUNT(*)
+        FROM requests
+        GROUP BY canonical_scramble_id
+        ORDER BY COUNT(*) DESC, MAX(id) DESC
+        LIMIT ?
+    ")?;
+    let most_requested = stmt.query_map([limit], |row| {
+        Ok(ScrambleCount {
+            canonical_scramble_id: row.get(0)?,
+            scramble: row.get(1)?,
+            requests: row.get(3)?,
+        })
+    })?.collect::<rusqlite::Result<Vec<ScrambleCount>>>()?;
+    let mut stmt = conn.prepare("
+        SELECT pipeline, COUNT(*), AVG(length), AVG(time_ms)
+        FROM solutions
+        GROUP BY pipeline
+        ORDER BY COUNT(*) DESC
+    ")?;
+    let pipelines = stmt.query_map([], |row| {
+        Ok(PipelineStats {
+            pipeline: row.get(0)?,
+            solutions: row.get(1)?,
+            average_length: row.get(2)?,
+            average_time_ms: row.get(3)?,
+        })
+    })?.collect::<rusqlite::Result<Vec<PipelineStats>>>()?;
+    Ok(StatsResponse {
+        total_requests,
+        total_solutions,
+        most_requested,
+        pipelines,
+    })
+}
+
+fn recorded_solution(pipeline: String, length: usize, solution: String, time_ms: u64) -> rusqlite::Result<RecordedSolution> {
+    let solution = serde_json::from_str(solution.as_str())
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?;
+    Ok(RecordedSolution {
+        length,
+        pipeline,
+        solution,
+        time_ms,
+    })
+}
+
+fn decode_settings(settings: &str) -> Option<Vec<StepConfig>> {
+    let json = base64::engine::general_purpose::STANDARD.decode(settings).ok()?;
+    serde_json::from_slice(json.as_slice()).ok()
+}
+
+pub fn get_canonical_scramble_id(scramble: &Algorithm<Turn333>) -> String {
     let mut cube = Cube333::default();
     cube.apply_alg(scramble);
+    get_canonical_cube_id(&cube).0
+}
+
+//The same id for a cube and its inverse. The flag is set if the id belongs to the inverse
+pub fn get_canonical_cube_id(cube: &Cube333) -> (String, bool) {
+    let mut cube = *cube;
     let normal_encoded = serialize_cube_to_base64(&cube);
     cube.invert();
     let inverse_encoded = serialize_cube_to_base64(&cube);
-    std::cmp::min(normal_encoded, inverse_encoded)
+    if inverse_encoded < normal_encoded {
+        (inverse_encoded, true)
+    } else {
+        (normal_encoded, false)
+    }
 }
 
-pub fn init_db(conn: &Connection) -> rusqlite::Result<usize> {
-    create_requests_table(&conn)
+pub fn load_cached_solutions(conn: &Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row("SELECT solutions FROM solution_cache WHERE key = ?", [key], |row| row.get(0))
+        .optional()
 }
 
-fn create_requests_table(conn: &Connection) -> rusqlite::Result<usize> {
-    conn.execute("
-        CREATE TABLE IF NOT EXISTS requests (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            scramble TEXT NOT NULL,
-            timestamp INTEGER NOT NULL,
-            canonical_scramble_id TEXT NOT NULL,
-            settings TEXT NOT NULL
-        )
-    ", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS id ON requests (scramble)", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS id ON requests (canonical_scramble_id)", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS id ON requests (canonical_scramble_inv_id)", [])
+pub fn store_cached_solutions(conn: &Connection, key: &str, solutions: &str) -> rusqlite::Result<()> {
+    let time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).expect("System time before unix epoch").as_secs();
+    conn.execute(
+        "INSERT OR REPLACE INTO solution_cache (key, timestamp, solutions) VALUES (?, ?, ?)",
+        (key, time, solutions)
+    ).map(|_|())
+}
+
+pub fn init_db(conn: &Connection) -> Result<(), String> {
+    migrations::migrate(conn)
 }
 
 fn serialize_cube_to_base64(cube: &Cube333) -> String {
@@ -6,19 +6,34 @@ use actix_cors::Cors;
 use actix_web::{App, get, HttpRequest, HttpResponse, HttpServer, Responder, web};
 use actix_web_lab::body;
 use cubelib::puzzles::c333::steps::tables::PruningTables333;
-use log::LevelFilter;
+use log::{error, info, LevelFilter};
 use moka::sync::Cache;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use simple_logger::SimpleLogger;
 
+use crate::cache::SolutionCache;
+use crate::config::Config;
+use crate::metrics::Metrics;
+use crate::queue::JobQueue;
+
+mod cache;
+mod config;
 mod controller;
 mod db;
+mod metrics;
+mod migrations;
+mod queue;
+mod socket;
 
 struct AppData {
+    config: Arc<Config>,
     pruning_tables: Arc<PruningTables333>,
     pool: Pool<SqliteConnectionManager>,
     debounce_cache: Arc<Cache<u64, SocketAddr>>,
+    job_queue: Arc<JobQueue>,
+    solution_cache: Arc<SolutionCache>,
+    metrics: Arc<Metrics>,
 }
 
 #[actix_web::main]
@@ -28,39 +43,78 @@ async fn main() -> std::io::Result<()> {
         .init()
         .unwrap();
 
-    let manager = SqliteConnectionManager::file("requests.db");
+    let config = match Config::load() {
+        Ok(config) => Arc::new(config),
+        Err(err) => {
+            error!("{err}");
+            std::process::exit(1);
+        }
+    };
+
+    let manager = SqliteConnectionManager::file(&config.db);
     let pool = Pool::new(manager).unwrap();
     let conn = pool.get().expect("Connection required");
-    db::init_db(&conn).unwrap();
+    if let Err(err) = db::init_db(&conn) {
+        error!("{err}");
+        std::process::exit(1);
+    }
 
     let mut pruning_tables = PruningTables333::new();
-    pruning_tables.gen_eo();
-    pruning_tables.gen_dr();
-    pruning_tables.gen_htr();
-    pruning_tables.gen_fr();
-    pruning_tables.gen_fr_leave_slice();
-    pruning_tables.gen_fr_finish();
-    pruning_tables.gen_htr_finish();
+    if let Some(dir) = config.table_dir.clone() {
+        pruning_tables.registry_mut().set_dir(dir);
+    }
+    let preload: Vec<String> = match &config.tables {
+        Some(tables) => tables.clone(),
+        None => pruning_tables.keys().into_iter().map(|key| key.to_string()).collect(),
+    };
+    let metrics = Arc::new(Metrics::new());
+    for key in preload {
+        if let Err(err) = pruning_tables.gen(key.as_str()) {
+            error!("{err}");
+            std::process::exit(1);
+        }
+        if let Some(bytes) = pruning_tables.registry().get_bytes(key.as_str()) {
+            metrics.pruning_table(key.as_str(), bytes.len());
+        }
+    }
 
     let pruning_tables = Arc::new(pruning_tables);
     let debounce_cache = Arc::new(Cache::builder()
-        .time_to_live(Duration::from_millis(500))
+        .time_to_live(config.debounce)
         .max_capacity(1000)
         .build()
     );
 
+    info!("Running up to {} solves at once with {} queued", config.max_solves, config.max_queued_solves);
+    let job_queue = Arc::new(JobQueue::new(config.max_solves, config.max_queued_solves));
+
+    let solution_cache = Arc::new(SolutionCache::new(config.solution_cache_size, config.solution_cache_sqlite));
+
+    let bind = config.bind.clone();
+
     HttpServer::new(move || {
         let cors = Cors::permissive();
         App::new()
             .app_data(web::Data::new(AppData {
+                config: config.clone(),
                 pruning_tables: pruning_tables.clone(),
                 pool: pool.clone(),
                 debounce_cache: debounce_cache.clone(),
+                job_queue: job_queue.clone(),
+                solution_cache: solution_cache.clone(),
+                metrics: metrics.clone(),
             }))
             .wrap(cors)
             .service(controller::solve_stream)
+            .service(controller::analyze)
+            .service(controller::verify)
+            .service(controller::history)
+            .service(controller::best_solutions)
+            .service(controller::stats)
+            .service(controller::prometheus_metrics)
+            .service(socket::solve_socket)
     })
-        .bind(("127.0.0.1", 8049))?
+        .bind(bind)?
         .run()
         .await
 }
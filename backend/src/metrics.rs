@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::queue::JobQueue;
+
+//Upper bounds in seconds. Solves are cut off after 60 seconds by default, so there is no point in going higher
+const SOLVE_TIME_BUCKETS: [f64; 12] = [0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 40.0, 60.0];
+
+//Counters for GET /metrics, rendered in the Prometheus text format
+pub struct Metrics {
+    requests: Mutex<BTreeMap<(&'static str, &'static str), u64>>,
+    solutions_streamed: AtomicU64,
+    cancellations: Mutex<BTreeMap<&'static str, u64>>,
+    debounce_kills: AtomicU64,
+    time_to_first_solution: Histogram,
+    time_to_best_solution: Histogram,
+    //Serialized size by table. Serializing on every scrape would copy the largest tables each time, so sizes are recorded once at startup
+    pruning_table_bytes: Mutex<BTreeMap<String, usize>>,
+}
+
+struct Histogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Metrics {
+        Metrics {
+            requests: Mutex::new(BTreeMap::new()),
+            solutions_streamed: AtomicU64::new(0),
+            cancellations: Mutex::new(BTreeMap::new()),
+            debounce_kills: AtomicU64::new(0),
+            time_to_first_solution: Histogram::new(),
+            time_to_best_solution: Histogram::new(),
+            pruning_table_bytes: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn request(&self, endpoint: &'static str, outcome: &'static str) {
+        *self.requests.lock().unwrap().entry((endpoint, outcome)).or_default() += 1;
+    }
+
+    pub fn solution_streamed(&self) {
+        self.solutions_streamed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn cancellation(&self, reason: &'static str) {
+        *self.cancellations.lock().unwrap().entry(reason).or_default() += 1;
+    }
+
+    pub fn debounce_kill(&self) {
+        self.debounce_kills.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn first_solution(&self, elapsed: Duration) {
+        self.time_to_first_solution.observe(elapsed);
+    }
+
+    pub fn best_solution(&self, elapsed: Duration) {
+        self.time_to_best_solution.observe(elapsed);
+    }
+
+    pub fn pruning_table(&self, key: &str, bytes: usize) {
+        self.pruning_table_bytes.lock().unwrap().insert(key.to_string(), bytes);
+    }
+
+    pub fn render(&self, queue: &JobQueue) -> String {
+        let mut out = String::new();
+        header(&mut out, "cubelib_requests_total", "counter", "Handled requests by endpoint and outcome");
+        for ((endpoint, outcome), count) in self.requests.lock().unwrap().iter() {
+            writeln!(out, "cubelib_requests_total{{endpoint=\"{endpoint}\",outcome=\"{outcome}\"}} {count}").unwrap();
+        }
+        header(&mut out, "cubelib_active_solves", "gauge", "Solves that are currently running");
+        writeln!(out, "cubelib_active_solves {}", queue.running()).unwrap();
+        header(&mut out, "cubelib_queued_solves", "gauge", "Solve requests waiting for a free slot");
+        writeln!(out, "cubelib_queued_solves {}", queue.queued()).unwrap();
+        self.time_to_first_solution.render(&mut out, "cubelib_time_to_first_solution_seconds", "Time from receiving a solve request to sending the first solution");
+        self.time_to_best_solution.render(&mut out, "cubelib_time_to_best_solution_seconds", "Time from receiving a solve request to sending the last and shortest solution");
+        header(&mut out, "cubelib_solutions_streamed_total", "counter", "Solutions sent to clients, including cached ones");
+        writeln!(out, "cubelib_solutions_streamed_total {}", self.solutions_streamed.load(Ordering::Relaxed)).unwrap();
+        header(&mut out, "cubelib_cancellations_total", "counter", "Solves that were stopped early, by reason");
+        for (reason, count) in self.cancellations.lock().unwrap().iter() {
+            writeln!(out, "cubelib_cancellations_total{{reason=\"{reason}\"}} {count}").unwrap();
+        }
+        header(&mut out, "cubelib_debounce_kills_total", "counter", "Duplicate solve requests that were rejected");
+        writeln!(out, "cubelib_debounce_kills_total {}", self.debounce_kills.load(Ordering::Relaxed)).unwrap();
+        header(&mut out, "cubelib_pruning_table_bytes", "gauge", "Size of the pruning tables loaded at startup");
+        for (table, bytes) in self.pruning_table_bytes.lock().unwrap().iter() {
+            writeln!(out, "cubelib_pruning_table_bytes{{table=\"{table}\"}} {bytes}").unwrap();
+        }
+        out
+    }
+}
+
+impl Histogram {
+    fn new() -> Histogram {
+        Histogram {
+            buckets: SOLVE_TIME_BUCKETS.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        for (bound, bucket) in SOLVE_TIME_BUCKETS.iter().zip(self.buckets.iter()) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        header(out, name, "histogram", help);
+        for (bound, bucket) in SOLVE_TIME_BUCKETS.iter().zip(self.buckets.iter()) {
+            writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {}", bucket.load(Ordering::Relaxed)).unwrap();
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {count}").unwrap();
+        writeln!(out, "{name}_sum {}", self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0).unwrap();
+        writeln!(out, "{name}_count {count}").unwrap();
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(out, "# HELP {name} {help}").unwrap();
+    writeln!(out, "# TYPE {name} {kind}").unwrap();
+}
@@ -0,0 +1,155 @@
+use log::info;
+
+use crate::db::Connection;
+
+//Schema changes in the order they are applied. Never edit or reorder a migration that has been released, add a new one instead.
+//The first ones use IF NOT EXISTS because they also run on databases that were created before there were migrations
+const MIGRATIONS: [&str; 3] = [
+    //1: Request log. Replaces the old indexes, which all had the same name, so only the one on scramble was ever created
+    "
+    CREATE TABLE IF NOT EXISTS requests (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        scramble TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        canonical_scramble_id TEXT NOT NULL,
+        settings TEXT NOT NULL
+    );
+    DROP INDEX IF EXISTS id;
+    CREATE INDEX requests_scramble ON requests (scramble);
+    CREATE INDEX requests_canonical_scramble_id ON requests (canonical_scramble_id);
+    CREATE INDEX requests_timestamp ON requests (timestamp);
+    ",
+    //2: Solution cache
+    "
+    CREATE TABLE IF NOT EXISTS solution_cache (
+        key TEXT PRIMARY KEY,
+        timestamp INTEGER NOT NULL,
+        solutions TEXT NOT NULL
+    );
+    ",
+    //3: Best solution per request
+    "
+    CREATE TABLE solutions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        request_id INTEGER NOT NULL REFERENCES requests (id),
+        pipeline TEXT NOT NULL,
+        length INTEGER NOT NULL,
+        solution TEXT NOT NULL,
+        time_ms INTEGER NOT NULL
+    );
+    CREATE INDEX solutions_request_id ON solutions (request_id);
+    CREATE INDEX solutions_pipeline ON solutions (pipeline);
+    ",
+];
+
+fn latest_version() -> u32 {
+    MIGRATIONS.len() as u32
+}
+
+//Brings the schema up to date. Fails if the database was migrated by a newer version of the backend
+pub fn migrate(conn: &Connection) -> Result<(), String> {
+    conn.execute("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL)", [])
+        .map_err(|e| e.to_string())?;
+    let version = schema_version(conn)?;
+    if version > latest_version() {
+        return Err(format!("Database schema version {version} is newer than the latest version {} this backend supports", latest_version()));
+    }
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let target = i as u32 + 1;
+        info!("Migrating database to schema version {target}");
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        tx.execute_batch(migration).map_err(|e| format!("Migration to schema version {target} failed: {e}"))?;
+        tx.execute("DELETE FROM schema_version", []).map_err(|e| e.to_string())?;
+        tx.execute("INSERT INTO schema_version (version) VALUES (?)", [target]).map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn schema_version(conn: &Connection) -> Result<u32, String> {
+    conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get::<_, Option<u32>>(0))
+        .map(|version| version.unwrap_or(0))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    use super::*;
+
+    fn connection() -> Connection {
+        //Every connection to an in memory database has its own database, so the pool must only have one
+        r2d2::Pool::builder()
+            .max_size(1)
+            .build(SqliteConnectionManager::memory())
+            .unwrap()
+            .get()
+            .unwrap()
+    }
+
+    fn indexes(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = ? AND name NOT LIKE 'sqlite_%' ORDER BY name").unwrap();
+        let rows = stmt.query_map([table], |row| row.get(0)).unwrap();
+        rows.collect::<rusqlite::Result<Vec<String>>>().unwrap()
+    }
+
+    fn insert_request(conn: &Connection) {
+        conn.execute("INSERT INTO requests (scramble, timestamp, canonical_scramble_id, settings) VALUES ('R U', 0, 'abc', '')", []).unwrap();
+    }
+
+    #[test]
+    fn migrates_fresh_database() {
+        let conn = connection();
+        migrate(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Ok(latest_version()));
+        assert_eq!(indexes(&conn, "requests"), vec!["requests_canonical_scramble_id", "requests_scramble", "requests_timestamp"]);
+        assert_eq!(indexes(&conn, "solutions"), vec!["solutions_pipeline", "solutions_request_id"]);
+        conn.query_row("SELECT COUNT(*) FROM solution_cache", [], |row| row.get::<_, u32>(0)).unwrap();
+    }
+
+    #[test]
+    fn migrates_legacy_database() {
+        let conn = connection();
+        //The schema created before there were migrations
+        conn.execute_batch("
+            CREATE TABLE requests (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                scramble TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                canonical_scramble_id TEXT NOT NULL,
+                settings TEXT NOT NULL
+            );
+            CREATE INDEX id ON requests (scramble);
+        ").unwrap();
+        insert_request(&conn);
+        migrate(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Ok(latest_version()));
+        assert_eq!(indexes(&conn, "requests"), vec!["requests_canonical_scramble_id", "requests_scramble", "requests_timestamp"]);
+        let requests: u32 = conn.query_row("SELECT COUNT(*) FROM requests", [], |row| row.get(0)).unwrap();
+        assert_eq!(requests, 1);
+    }
+
+    #[test]
+    fn rerun_at_latest_version_does_nothing() {
+        let conn = connection();
+        migrate(&conn).unwrap();
+        insert_request(&conn);
+        migrate(&conn).unwrap();
+        assert_eq!(schema_version(&conn), Ok(latest_version()));
+        let versions: u32 = conn.query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(versions, 1);
+        let requests: u32 = conn.query_row("SELECT COUNT(*) FROM requests", [], |row| row.get(0)).unwrap();
+        assert_eq!(requests, 1);
+    }
+
+    #[test]
+    fn refuses_newer_schema_version() {
+        let conn = connection();
+        migrate(&conn).unwrap();
+        conn.execute("UPDATE schema_version SET version = ?", [latest_version() + 1]).unwrap();
+        let expected = format!("Database schema version {} is newer than the latest version {} this backend supports", latest_version() + 1, latest_version());
+        assert_eq!(migrate(&conn), Err(expected));
+        assert_eq!(schema_version(&conn), Ok(latest_version() + 1));
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+//Limits the number of solves that run at the same time. Requests that don't get a slot wait in a bounded FIFO queue
+pub struct JobQueue {
+    max_running: usize,
+    max_queued: usize,
+    state: Mutex<QueueState>,
+    slot_freed: Condvar,
+}
+
+struct QueueState {
+    running: usize,
+    waiting: VecDeque<u64>,
+    next_id: u64,
+}
+
+//A place in the queue. Dropping it before it turned into a slot leaves the queue
+pub struct Ticket {
+    id: u64,
+    queue: Arc<JobQueue>,
+    acquired: bool,
+}
+
+//Permission to run a solve. The slot is freed as soon as this is dropped
+pub struct Slot {
+    queue: Arc<JobQueue>,
+}
+
+impl JobQueue {
+    pub fn new(max_running: usize, max_queued: usize) -> JobQueue {
+        JobQueue {
+            max_running: max_running.max(1),
+            max_queued,
+            state: Mutex::new(QueueState {
+                running: 0,
+                waiting: VecDeque::new(),
+                next_id: 0,
+            }),
+            slot_freed: Condvar::new(),
+        }
+    }
+
+    //Returns None if the queue is full. Tickets that can take one of the free slots right away don't count towards max_queued
+    pub fn enqueue(self: &Arc<Self>) -> Option<Ticket> {
+        let mut state = self.state.lock().unwrap();
+        if state.waiting.len() >= self.max_queued + self.max_running.saturating_sub(state.running) {
+            return None;
+        }
+        let id = state.next_id;
+        state.next_id += 1;
+        state.waiting.push_back(id);
+        Some(Ticket {
+            id,
+            queue: self.clone(),
+            acquired: false,
+        })
+    }
+
+    pub fn running(&self) -> usize {
+        self.state.lock().unwrap().running
+    }
+
+    pub fn queued(&self) -> usize {
+        self.state.lock().unwrap().waiting.len()
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.running -= 1;
+        self.slot_freed.notify_all();
+    }
+}
+
+impl Ticket {
+    //Waits up to the timeout for a free slot. If there is none, the 1 based position in the queue is returned
+    pub fn wait(mut self, timeout: Duration) -> Result<Slot, (Ticket, usize)> {
+        let queue = self.queue.clone();
+        let mut state = queue.state.lock().unwrap();
+        if !self.is_next(&state) {
+            state = queue.slot_freed.wait_timeout(state, timeout).unwrap().0;
+        }
+        if self.is_next(&state) {
+            state.waiting.pop_front();
+            state.running += 1;
+            self.acquired = true;
+            //The next ticket might be able to start as well
+            queue.slot_freed.notify_all();
+            Ok(Slot {
+                queue: self.queue.clone(),
+            })
+        } else {
+            let position = state.waiting.iter().position(|id| *id == self.id).unwrap() + 1;
+            drop(state);
+            Err((self, position))
+        }
+    }
+
+    fn is_next(&self, state: &QueueState) -> bool {
+        state.running < self.queue.max_running && state.waiting.front() == Some(&self.id)
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        if self.acquired {
+            return;
+        }
+        let mut state = self.queue.state.lock().unwrap();
+        state.waiting.retain(|id| *id != self.id);
+        //Tickets behind this one moved up
+        self.queue.slot_freed.notify_all();
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Instant;
+
+    use super::*;
+
+    const NO_WAIT: Duration = Duration::from_millis(0);
+
+    fn position(result: Result<Slot, (Ticket, usize)>) -> (Ticket, usize) {
+        match result {
+            Ok(_) => panic!("Expected the ticket to keep waiting"),
+            Err(waiting) => waiting,
+        }
+    }
+
+    #[test]
+    fn enqueue_is_bounded_by_free_slots_and_queue_length() {
+        let queue = Arc::new(JobQueue::new(2, 1));
+        let tickets: Vec<Ticket> = (0..3).map(|_| queue.enqueue().unwrap()).collect();
+        assert!(queue.enqueue().is_none());
+        assert_eq!(queue.queued(), 3);
+
+        let mut tickets = tickets.into_iter();
+        let _first = tickets.next().unwrap().wait(NO_WAIT).ok().unwrap();
+        let _second = tickets.next().unwrap().wait(NO_WAIT).ok().unwrap();
+        assert_eq!(queue.running(), 2);
+        assert_eq!(queue.queued(), 1);
+        assert!(queue.enqueue().is_none());
+    }
+
+    #[test]
+    fn tickets_get_slots_in_fifo_order() {
+        let queue = Arc::new(JobQueue::new(1, 5));
+        let first = queue.enqueue().unwrap();
+        let second = queue.enqueue().unwrap();
+        let third = queue.enqueue().unwrap();
+
+        let (second, position_second) = position(second.wait(NO_WAIT));
+        assert_eq!(position_second, 2);
+        let slot = first.wait(NO_WAIT).ok().unwrap();
+        let (third, position_third) = position(third.wait(NO_WAIT));
+        assert_eq!(position_third, 2);
+        let (second, position_second) = position(second.wait(NO_WAIT));
+        assert_eq!(position_second, 1);
+
+        drop(slot);
+        let (third, _) = position(third.wait(NO_WAIT));
+        let slot = second.wait(NO_WAIT).ok().unwrap();
+        drop(slot);
+        assert!(third.wait(NO_WAIT).is_ok());
+    }
+
+    #[test]
+    fn dropping_a_slot_frees_it() {
+        let queue = Arc::new(JobQueue::new(1, 1));
+        let slot = queue.enqueue().unwrap().wait(NO_WAIT).ok().unwrap();
+        let waiting = queue.enqueue().unwrap();
+        assert_eq!(queue.running(), 1);
+
+        let started = Instant::now();
+        let handle = thread::spawn(move || waiting.wait(Duration::from_secs(10)).is_ok());
+        thread::sleep(Duration::from_millis(50));
+        drop(slot);
+        assert!(handle.join().unwrap());
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(queue.running(), 0);
+        assert_eq!(queue.queued(), 0);
+    }
+
+    #[test]
+    fn dropping_a_ticket_leaves_the_queue() {
+        let queue = Arc::new(JobQueue::new(1, 2));
+        let _slot = queue.enqueue().unwrap().wait(NO_WAIT).ok().unwrap();
+        let first = queue.enqueue().unwrap();
+        let second = queue.enqueue().unwrap();
+        assert!(queue.enqueue().is_none());
+
+        drop(first);
+        assert_eq!(queue.queued(), 1);
+        let (_second, position) = position(second.wait(NO_WAIT));
+        assert_eq!(position, 1);
+        assert!(queue.enqueue().is_some());
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use actix_web::{get, HttpRequest, HttpResponse, web};
+use actix_ws::Message;
+use cubelib::algs::Algorithm;
+use cubelib::puzzles::c333::Cube333;
+use cubelib::puzzles::puzzle::ApplyAlgorithm;
+use cubelib::solver::CancellationToken;
+use cubelib_interface::{SolverError, SolverRequest, SolverSocketEvent, SolverSocketRequest, SolverUpdate};
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::StreamExt;
+use log::{debug, error, info};
+
+use crate::{AppData, db};
+use crate::cache::CacheKey;
+use crate::controller::{QUEUE_FULL_RETRY_AFTER_SECS, solve_steps_quality_doubling, validate_steps};
+
+const SOLVE_SOCKET: &str = "solve_socket";
+
+//A solve on a socket connection. The time budget can be changed by the client while the solve runs
+struct SocketJob {
+    cancel_token: CancellationToken,
+    cancelled_by_client: AtomicBool,
+    timed_out: AtomicBool,
+    time_budget_ms: AtomicU64,
+}
+
+//Like /solve_stream, but with any number of solves per connection that can be cancelled or given more time by sending a message.
+//See SolverSocketRequest and SolverSocketEvent for the protocol, all messages are JSON text frames
+#[get("/solve_socket")]
+pub async fn solve_socket(req: HttpRequest, body: web::Payload, app_data: web::Data<AppData>) -> actix_web::Result<HttpResponse> {
+    let (response, mut session, mut messages) = actix_ws::handle(&req, body)?;
+    let (event_tx, mut event_rx) = unbounded::<SolverSocketEvent>();
+
+    let mut writer = session.clone();
+    actix_web::rt::spawn(async move {
+        while let Some(event) = event_rx.next().await {
+            if writer.text(serde_json::to_string(&event).unwrap()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    actix_web::rt::spawn(async move {
+        let mut jobs: HashMap<u64, Arc<SocketJob>> = HashMap::new();
+        while let Some(Ok(msg)) = messages.next().await {
+            match msg {
+                Message::Text(text) => {
+                    //Solves cancel their token once they are done
+                    jobs.retain(|_, job| !job.cancel_token.is_cancelled());
+                    match serde_json::from_str::<SolverSocketRequest>(&text) {
+                        Ok(request) => handle_request(request, &mut jobs, &event_tx, &app_data),
+                        Err(err) => {
+                            let _ = event_tx.unbounded_send(error_event(None, SolverError::InvalidRequest { message: err.to_string() }));
+                        }
+                    }
+                },
+                Message::Ping(bytes) => {
+                    if session.pong(&bytes).await.is_err() {
+                        break;
+                    }
+                },
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+        info!("Socket closed, cancelling {} solves", jobs.len());
+        //Running solves notice the closed channel when they try to send their next event
+        event_tx.close_channel();
+        for job in jobs.values() {
+            job.cancel_token.cancel();
+        }
+        let _ = session.close(None).await;
+    });
+
+    Ok(response)
+}
+
+fn handle_request(request: SolverSocketRequest, jobs: &mut HashMap<u64, Arc<SocketJob>>, event_tx: &UnboundedSender<SolverSocketEvent>, app_data: &web::Data<AppData>) {
+    let max_budget = app_data.config.solve_timeout.as_millis() as u64;
+    match request {
+        SolverSocketRequest::Solve { id, request, time_budget_ms } => {
+            if jobs.contains_key(&id) {
+                let _ = event_tx.unbounded_send(error_event(Some(id), SolverError::InvalidRequest { message: format!("Solve {id} is already running") }));
+                return;
+            }
+            let job = Arc::new(SocketJob {
+                cancel_token: CancellationToken::new(),
+                cancelled_by_client: AtomicBool::new(false),
+                timed_out: AtomicBool::new(false),
+                time_budget_ms: AtomicU64::new(time_budget_ms.unwrap_or(max_budget).min(max_budget)),
+            });
+            jobs.insert(id, job.clone());
+            let event_tx = event_tx.clone();
+            let app_data = app_data.clone();
+            let _ = web::block(move || run_job(id, request, job, event_tx, app_data));
+        },
+        SolverSocketRequest::Cancel { id } => {
+            if let Some(job) = jobs.remove(&id) {
+                debug!("Cancelling solve {id}");
+                job.cancelled_by_client.store(true, Ordering::Relaxed);
+                job.cancel_token.cancel();
+            }
+        },
+        SolverSocketRequest::SetTimeBudget { id, time_budget_ms } => {
+            match jobs.get(&id) {
+                Some(job) => job.time_budget_ms.store(time_budget_ms.min(max_budget), Ordering::Relaxed),
+                None => {
+                    let _ = event_tx.unbounded_send(error_event(Some(id), SolverError::InvalidRequest { message: format!("Solve {id} is not running") }));
+                }
+            }
+        },
+    }
+}
+
+fn error_event(id: Option<u64>, error: SolverError) -> SolverSocketEvent {
+    SolverSocketEvent::new(id, SolverUpdate::Error { error })
+}
+
+fn run_job(id: u64, request: SolverRequest, job: Arc<SocketJob>, event_tx: UnboundedSender<SolverSocketEvent>, app_data: web::Data<AppData>) {
+    let received = Instant::now();
+    let max_budget = app_data.config.solve_timeout.as_millis() as u64;
+    let metrics = app_data.metrics.clone();
+    let send = |update: SolverUpdate| event_tx.unbounded_send(SolverSocketEvent::new(Some(id), update)).is_ok();
+    let fail = |error: SolverError, outcome: &'static str| {
+        metrics.request(SOLVE_SOCKET, outcome);
+        job.cancel_token.cancel();
+        send(SolverUpdate::Error { error });
+        send(SolverUpdate::Done { cancelled: false });
+    };
+
+    let SolverRequest { steps, scramble } = request;
+    let scramble = match Algorithm::from_str(scramble.as_str()) {
+        Ok(scramble) => scramble,
+        Err(_) => return fail(SolverError::InvalidScramble { scramble }, "bad_request"),
+    };
+    if let Err(message) = validate_steps(&steps, &app_data) {
+        return fail(SolverError::InvalidStepConfig { message }, "bad_request");
+    }
+    let mut cube = Cube333::default();
+    cube.apply_alg(&scramble);
+
+    let conn = match app_data.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            error!("{err}");
+            return fail(SolverError::Internal, "error");
+        }
+    };
+    let request_id = match db::record_request(&conn, &scramble, &steps, app_data.pruning_tables.as_ref()) {
+        Ok(request_id) => request_id,
+        Err(err) => {
+            error!("{err}");
+            return fail(SolverError::Internal, "error");
+        }
+    };
+    let cache_key = CacheKey::new(&cube, &steps);
+    if let Some(cached) = app_data.solution_cache.get(&cache_key, &conn) {
+        info!("Returning cached solutions for {scramble}");
+        metrics.request(SOLVE_SOCKET, "cached");
+        //Recorded with the time of the request that found the solutions, not the time of the lookup
+        if let (Some(best), Some(found)) = (cached.solutions.last(), cached.best_found) {
+            if let Err(err) = db::record_solution(&conn, request_id, &steps, best, found) {
+                error!("{err}");
+            }
+        }
+        for solution in cached.solutions {
+            metrics.solution_streamed();
+            send(SolverUpdate::Solution { solution });
+        }
+        job.cancel_token.cancel();
+        send(SolverUpdate::Done { cancelled: false });
+        return;
+    }
+    drop(conn);
+
+    let mut ticket = match app_data.job_queue.enqueue() {
+        Some(ticket) => ticket,
+        None => return fail(SolverError::Overloaded { retry_after_secs: QUEUE_FULL_RETRY_AFTER_SECS }, "queue_full"),
+    };
+    let mut last_position = None;
+    let mut timeout = Duration::ZERO;
+    let _slot = loop {
+        match ticket.wait(timeout) {
+            Ok(slot) => break slot,
+            Err((t, position)) => {
+                ticket = t;
+                timeout = Duration::from_secs(1);
+                if job.cancel_token.is_cancelled() {
+                    metrics.request(SOLVE_SOCKET, "cancelled");
+                    metrics.cancellation("cancelled");
+                    send(SolverUpdate::Done { cancelled: true });
+                    return;
+                }
+                if last_position != Some(position) {
+                    last_position = Some(position);
+                    let elapsed_ms = received.elapsed().as_millis() as u64;
+                    if !send(SolverUpdate::Progress { queue_position: Some(position), quality: None, elapsed_ms }) {
+                        metrics.request(SOLVE_SOCKET, "disconnected");
+                        metrics.cancellation("disconnected");
+                        job.cancel_token.cancel();
+                        return;
+                    }
+                }
+            }
+        }
+    };
+
+    info!("Solving {scramble} for socket request {id}");
+    let watched_job = job.clone();
+    let watcher_metrics = metrics.clone();
+    let _ = web::block(move || {
+        let started = Instant::now();
+        while !watched_job.cancel_token.is_cancelled() {
+            sleep(Duration::from_millis(100));
+            if started.elapsed().as_millis() as u64 > watched_job.time_budget_ms.load(Ordering::Relaxed) {
+                watcher_metrics.cancellation("timeout");
+                watched_job.timed_out.store(true, Ordering::Relaxed);
+                watched_job.cancel_token.cancel();
+            }
+        }
+    });
+
+    let progress_tx = event_tx.clone();
+    let progress_token = job.cancel_token.clone();
+    let solutions = solve_steps_quality_doubling(cube, steps.clone(), app_data.config.max_quality, app_data.pruning_tables.clone(), job.cancel_token.clone(), move |quality| {
+        if progress_token.is_cancelled() {
+            return;
+        }
+        let elapsed_ms = received.elapsed().as_millis() as u64;
+        let _ = progress_tx.unbounded_send(SolverSocketEvent::new(Some(id), SolverUpdate::Progress { queue_position: None, quality: Some(quality), elapsed_ms }));
+    });
+    let mut sent = vec![];
+    let mut best_found = None;
+    for solution in solutions {
+        if job.cancel_token.is_cancelled() {
+            break;
+        }
+        if !send(SolverUpdate::Solution { solution: solution.clone() }) {
+            break;
+        }
+        if sent.is_empty() {
+            metrics.first_solution(received.elapsed());
+        }
+        best_found = Some(received.elapsed());
+        metrics.solution_streamed();
+        sent.push(solution);
+    }
+    //The solver stops early when the token is cancelled, e.g. by the timeout. Those results aren't the best ones it can find
+    let finished = !job.cancel_token.is_cancelled();
+    job.cancel_token.cancel();
+
+    //The best solution is recorded even if the client is gone
+    let conn = match app_data.pool.get() {
+        Ok(conn) => Some(conn),
+        Err(err) => {
+            error!("{err}");
+            None
+        }
+    };
+    if let (Some(conn), Some(best), Some(found)) = (&conn, sent.last(), best_found) {
+        if let Err(err) = db::record_solution(conn, request_id, &steps, best, found) {
+            error!("{err}");
+        }
+    }
+
+    let cancelled = job.cancelled_by_client.load(Ordering::Relaxed);
+    if sent.is_empty() && job.timed_out.load(Ordering::Relaxed) {
+        send(SolverUpdate::Error { error: SolverError::Timeout });
+    }
+    if !send(SolverUpdate::Done { cancelled }) {
+        metrics.request(SOLVE_SOCKET, "disconnected");
+        metrics.cancellation("disconnected");
+        return;
+    }
+    if cancelled {
+        metrics.request(SOLVE_SOCKET, "cancelled");
+        metrics.cancellation("cancelled");
+        return;
+    }
+    metrics.request(SOLVE_SOCKET, "streamed");
+    if let Some(found) = best_found {
+        metrics.best_solution(found);
+    }
+    //A shorter budget may have cut off solutions that a request with the default budget would get
+    if !finished || job.time_budget_ms.load(Ordering::Relaxed) < max_budget {
+        return;
+    }
+    if let Some(conn) = conn {
+        app_data.solution_cache.insert(&cache_key, sent, best_found, &conn);
+    }
+}
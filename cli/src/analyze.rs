@@ -0,0 +1,60 @@
+use std::str::FromStr;
+
+use cubelib::algs::Algorithm;
+use cubelib::puzzles::c333::{Cube333, Turn333};
+use cubelib::puzzles::c333::steps::analysis::{self, Analysis333, AxisValues};
+use cubelib::puzzles::c333::steps::tables::PruningTables333;
+use cubelib::puzzles::puzzle::ApplyAlgorithm;
+
+use crate::output::OutputFormat;
+
+pub fn run(scramble: &str, format: OutputFormat) -> Result<(), String> {
+    let scramble = Algorithm::<Turn333>::from_str(scramble).map_err(|_| format!("Invalid scramble {scramble}"))?;
+    let mut cube = Cube333::default();
+    cube.apply_alg(&scramble);
+
+    let tables = PruningTables333::new();
+    for key in tables.keys() {
+        tables.gen(key)?;
+    }
+    print(&analysis::analyze(&cube, &tables), format)
+}
+
+pub fn print(analysis: &Analysis333, format: OutputFormat) -> Result<(), String> {
+    match format {
+        OutputFormat::Text => print_text(analysis),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(analysis).map_err(|e| e.to_string())?),
+        OutputFormat::Ndjson => println!("{}", serde_json::to_string(analysis).map_err(|e| e.to_string())?),
+    }
+    Ok(())
+}
+
+fn print_text(analysis: &Analysis333) {
+    println!("{:<12} {}", "Bad edges", axis_values(&analysis.bad_edges, |v| v.to_string()));
+    println!("{:<12} {}", "Bad corners", axis_values(&analysis.bad_corners, |v| v.to_string()));
+    println!("{:<12} {}", "EO", axis_values(&analysis.eo, yes_no));
+    println!("{:<12} {}", "DR", axis_values(&analysis.dr, yes_no));
+    println!("{:<12} {}", "HTR", yes_no(&analysis.htr));
+    println!("{:<12} {}", "FR", axis_values(&analysis.fr, yes_no));
+    if [&analysis.htr_subset.ud, &analysis.htr_subset.fb, &analysis.htr_subset.lr].iter().any(|s| s.is_some()) {
+        println!("{:<12} {}", "HTR subset", axis_values(&analysis.htr_subset, |s| s.clone().unwrap_or("-".to_string())));
+    }
+    println!();
+    println!("Optimal distances");
+    for distance in analysis.distances.iter() {
+        let name = if distance.variant.is_empty() {
+            distance.kind.to_string()
+        } else {
+            format!("{}-{}", distance.kind, distance.variant)
+        };
+        println!("  {name:<16} {}", distance.distance);
+    }
+}
+
+fn axis_values<T>(values: &AxisValues<T>, f: impl Fn(&T) -> String) -> String {
+    format!("UD {:<8} FB {:<8} LR {}", f(&values.ud), f(&values.fb), f(&values.lr))
+}
+
+fn yes_no(value: &bool) -> String {
+    if *value { "yes" } else { "no" }.to_string()
+}
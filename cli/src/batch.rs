@@ -0,0 +1,179 @@
+use std::fs;
+use std::io::Read;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+
+use cubelib::algs::Algorithm;
+use cubelib::puzzles::c333::{Cube333, Turn333};
+use cubelib::puzzles::c333::steps::solver;
+use cubelib::puzzles::c333::steps::tables::PruningTables333;
+use cubelib::puzzles::puzzle::ApplyAlgorithm;
+use cubelib::steps::step::StepConfig;
+use log::info;
+use serde::Serialize;
+
+use crate::cli::{BatchArgs, BatchFormat, Cli};
+use crate::output::SolutionOutput;
+
+struct BatchScramble {
+    id: String,
+    scramble: String,
+}
+
+#[derive(Serialize)]
+struct BatchResult {
+    id: String,
+    scramble: String,
+    time_ms: f64,
+    error: Option<String>,
+    solutions: Vec<SolutionOutput>,
+}
+
+pub fn run(cli: &Cli, args: &BatchArgs) -> Result<(), String> {
+    let scrambles = read_scrambles(args)?;
+    let configs = cli.parse_step_configs().map_err(|e| format!("Unable to parse steps config. {e}"))?;
+
+    //The tables are only generated once and then shared by all threads. Steps borrow them, so every thread builds its own
+    let mut tables = PruningTables333::new();
+    solver::gen_tables(&configs, &mut tables);
+    solver::build_steps(configs.clone(), &tables)?;
+
+    let threads = args.threads
+        .unwrap_or_else(|| thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+        .clamp(1, scrambles.len().max(1));
+    info!("Solving {} scrambles on {threads} threads", scrambles.len());
+    let time = Instant::now();
+    let results = solve_parallel(cli, &scrambles, &configs, &tables, threads);
+    info!("Solved {} scrambles in {}ms", scrambles.len(), time.elapsed().as_millis());
+
+    match args.format {
+        BatchFormat::Csv => write_csv(&results),
+        BatchFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?);
+            Ok(())
+        }
+    }
+}
+
+fn solve_parallel(cli: &Cli, scrambles: &[BatchScramble], configs: &[StepConfig], tables: &PruningTables333, threads: usize) -> Vec<BatchResult> {
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, BatchResult)> = thread::scope(|s| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| s.spawn(|| {
+                let steps = solver::build_steps(configs.to_vec(), tables).expect("Steps were validated before");
+                let mut results = vec![];
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= scrambles.len() {
+                        break;
+                    }
+                    let result = solve_scramble(cli, &scrambles[i], &steps);
+                    info!("Solved {} in {:.1}ms", result.id, result.time_ms);
+                    results.push((i, result));
+                }
+                results
+            }))
+            .collect();
+        workers.into_iter()
+            .flat_map(|w| w.join().expect("Batch worker panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+fn solve_scramble(cli: &Cli, scramble: &BatchScramble, steps: &crate::Steps333) -> BatchResult {
+    let time = Instant::now();
+    let mut result = BatchResult {
+        id: scramble.id.clone(),
+        scramble: scramble.scramble.clone(),
+        time_ms: 0.0,
+        error: None,
+        solutions: vec![],
+    };
+    match Algorithm::<Turn333>::from_str(scramble.scramble.as_str()) {
+        Ok(alg) => {
+            let mut cube = Cube333::default();
+            cube.apply_alg(&alg);
+            result.solutions = crate::solve(cli, cube, steps)
+                .map(|solution| SolutionOutput::new(&alg, &solution))
+                .collect();
+            if result.solutions.is_empty() {
+                result.error = Some("No solution found".to_string());
+            }
+        },
+        Err(_) => result.error = Some("Invalid scramble".to_string()),
+    }
+    result.time_ms = time.elapsed().as_secs_f64() * 1000.0;
+    result
+}
+
+fn read_scrambles(args: &BatchArgs) -> Result<Vec<BatchScramble>, String> {
+    let input = match args.input.as_ref().filter(|p| p.to_str() != Some("-")) {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {e}", path.display()))?,
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).map_err(|e| format!("Unable to read stdin: {e}"))?;
+            input
+        }
+    };
+    let is_csv = input.lines()
+        .find(|l| !l.trim().is_empty())
+        .is_some_and(|header| header.split(',').any(|c| c.trim().eq_ignore_ascii_case("scramble")));
+    if is_csv {
+        read_csv(input.as_str())
+    } else {
+        //Without a header the id is the line number
+        Ok(input.lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(n, l)| BatchScramble {
+                id: (n + 1).to_string(),
+                scramble: l.trim().to_string(),
+            })
+            .collect())
+    }
+}
+
+fn read_csv(input: &str) -> Result<Vec<BatchScramble>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(input.as_bytes());
+    let headers = reader.headers().map_err(|e| format!("Invalid CSV header: {e}"))?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let scramble_column = column("scramble").ok_or("CSV input needs a scramble column")?;
+    let id_column = column("id");
+    let mut scrambles = vec![];
+    for (n, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Invalid CSV record: {e}"))?;
+        let scramble = record.get(scramble_column).unwrap_or("").to_string();
+        if scramble.is_empty() {
+            continue;
+        }
+        scrambles.push(BatchScramble {
+            id: id_column.and_then(|c| record.get(c)).map(|id| id.to_string()).unwrap_or_else(|| (n + 1).to_string()),
+            scramble,
+        });
+    }
+    Ok(scrambles)
+}
+
+//One row per solution, or a single row with an error if a scramble has none
+fn write_csv(results: &[BatchResult]) -> Result<(), String> {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+    writer.write_record(["id", "scramble", "length", "solution", "time_ms", "error"]).map_err(|e| e.to_string())?;
+    for result in results {
+        let time = format!("{:.3}", result.time_ms);
+        let error = result.error.as_deref().unwrap_or("");
+        if result.solutions.is_empty() {
+            writer.write_record([result.id.as_str(), result.scramble.as_str(), "", "", time.as_str(), error]).map_err(|e| e.to_string())?;
+        }
+        for solution in result.solutions.iter() {
+            let length = solution.length.to_string();
+            writer.write_record([result.id.as_str(), result.scramble.as_str(), length.as_str(), solution.solution.as_str(), time.as_str(), error]).map_err(|e| e.to_string())?;
+        }
+    }
+    writer.flush().map_err(|e| e.to_string())
+}
@@ -1,15 +1,21 @@
-use std::collections::HashMap;
+use std::path::PathBuf;
 use std::str::FromStr;
-use clap::Parser;
-use regex::Regex;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use cubelib::defs::*;
+use cubelib::steps::pipeline::Pipeline;
 use cubelib::steps::step::{StepConfig};
 
-#[derive(Parser)]
+use crate::output::OutputFormat;
+use crate::render::RenderStyle;
+
+#[derive(Parser, Clone)]
 #[command(name = "Cubelib")]
 #[command(author = "Jonas Balsfulland <cubelib@joba.me>")]
 #[command(version = "1.2")]
+#[command(subcommand_negates_reqs = true)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
     #[arg(short, long, default_value_t = false, group = "log_level", help = "Enables more detailed logging")]
     pub verbose: bool,
     #[arg(long, default_value_t = false, group = "log_level", help = "Prints nothing but the solutions")]
@@ -18,21 +24,114 @@ pub struct Cli {
     pub compact_solutions: bool,
     #[arg(short = 'p', long = "plain", default_value_t = false, requires = "compact", help = "Does not print the number of moves of the solution")]
     pub plain_solution: bool,
-    #[arg(short = 'a', long = "all", default_value_t = false, help = "Print solutions that would otherwise get filtered out. E.g. an EO ending in F'")]
+    #[arg(short = 'a', long = "all", default_value_t = false, overrides_with = "no_all_solutions", help = "Print solutions that would otherwise get filtered out. E.g. an EO ending in F'")]
     pub all_solutions: bool,
+    #[arg(long = "no-all", default_value_t = false, overrides_with = "all_solutions", help = "Filters solutions even if the profile sets all")]
+    pub no_all_solutions: bool,
     #[arg(short = 'm', long = "min", default_value_t = 0, help = "Minimum length of solutions")]
     pub min: usize,
     #[arg(short = 'M', long = "max", help = "Maximum length of solutions")]
     pub max: Option<usize>,
-    #[arg(short = 'N', long = "niss", default_value_t = false, help = "Allows using NISS in some parts of solution")]
+    #[arg(short = 'N', long = "niss", default_value_t = false, overrides_with = "no_niss", help = "Allows using NISS in some parts of solution")]
     pub niss: bool,
+    #[arg(long = "no-niss", default_value_t = false, overrides_with = "niss", help = "Disables NISS even if the profile enables it")]
+    pub no_niss: bool,
     #[arg(short = 'n', help = "The number of solutions returned. By default 1 unless this option or --max is set")]
     pub solution_count: Option<usize>,
     #[arg(short = 'q', long = "quality", default_value_t = 100, help = "Influences the maximum number of solutions calculated per step. Set to 0 for infinite quality")]
     pub quality: usize,
     #[arg(long = "steps", short = 's', default_value = "EO > RZP > DR[triggers=R,RUR,RU'R,RU2R] > HTR > FIN", help = "List of steps to perform")]
     pub steps: String,
-    pub scramble: String,
+    #[arg(long = "format", value_enum, default_value_t = OutputFormat::Text, help = "Output format of the solutions. json prints all solutions as one array, ndjson prints one solution per line")]
+    pub format: OutputFormat,
+    #[arg(long = "profile", global = true, help = "Name of a profile in the config file. Options passed on the command line override the profile")]
+    pub profile: Option<String>,
+    #[arg(long = "config", global = true, help = "Path of the config file. Defaults to ~/.cubelib/config.toml")]
+    pub config: Option<PathBuf>,
+    #[arg(required = true)]
+    pub scramble: Option<String>,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum Commands {
+    #[command(about = "Manage the pruning tables cached in ~/.cubelib/tables")]
+    Tables {
+        #[command(subcommand)]
+        command: TablesCommand,
+    },
+    #[command(about = "Solves all scrambles from a file or stdin. Uses the global options, e.g. `cubelib --steps \"EO > DR\" batch scrambles.txt`")]
+    Batch(BatchArgs),
+    #[command(about = "Prints the bad edges and corners, solved steps, HTR subset and the optimal distance to every step of a scramble")]
+    Analyze {
+        #[arg(long = "format", value_enum, default_value_t = OutputFormat::Text, help = "Output format of the analysis")]
+        format: OutputFormat,
+        scramble: String,
+    },
+    #[command(about = "Checks whether a solution solves a scramble and prints the subgroup after every step")]
+    Verify {
+        #[arg(long = "format", value_enum, default_value_t = OutputFormat::Text, help = "Output format of the result")]
+        format: OutputFormat,
+        scramble: String,
+        #[arg(help = "The solution, either as a single algorithm or with one `alg // label` step per line. Reads from stdin if omitted")]
+        solution: Option<String>,
+    },
+    #[command(about = "Prints the cube after a scramble, or after the steps of a solution, as colored blocks, letters or SVG")]
+    Render {
+        #[arg(long = "style", value_enum, default_value_t = RenderStyle::Ansi, help = "How the cube is printed. ansi needs a terminal with truecolor support")]
+        style: RenderStyle,
+        #[arg(long = "step", help = "Renders the state after this many steps of the solution. Defaults to all steps")]
+        step: Option<usize>,
+        #[arg(long = "mask", help = "Greys out stickers. One of eo-ud, eo-fb, eo-lr, dr-ud, dr-fb, dr-lr, htr, cross, corners, edges, or 54 characters of 1 (show) and 0 (hide) in URFDLB order")]
+        mask: Option<String>,
+        scramble: String,
+        #[arg(help = "Moves applied after the scramble, either as a single algorithm or with one `alg // label` step per line. Reads from stdin if -")]
+        solution: Option<String>,
+    },
+    #[command(about = "Starts an interactive session that keeps the pruning tables loaded between commands. Uses the global options as defaults")]
+    Repl,
+}
+
+#[derive(Args, Clone)]
+pub struct BatchArgs {
+    #[arg(help = "File with one scramble per line, or a CSV file with a header containing a scramble and optionally an id column. Reads from stdin if omitted or -")]
+    pub input: Option<PathBuf>,
+    #[arg(long = "format", value_enum, default_value_t = BatchFormat::Csv, help = "Output format of the results")]
+    pub format: BatchFormat,
+    #[arg(short = 't', long = "threads", help = "Number of scrambles solved in parallel. Defaults to the number of available cores")]
+    pub threads: Option<usize>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum BatchFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Subcommand, Clone)]
+pub enum TablesCommand {
+    #[command(about = "Generates the given tables, or loads them if they are already cached")]
+    Generate {
+        #[arg(long, default_value_t = false, conflicts_with = "keys", help = "Generates all tables")]
+        all: bool,
+        #[arg(long, default_value_t = false, help = "Regenerates tables even if they are already cached")]
+        force: bool,
+        #[arg(required_unless_present = "all")]
+        keys: Vec<String>,
+    },
+    #[command(about = "Lists all tables and whether they are cached")]
+    List,
+    #[command(about = "Regenerates cached tables and compares them with the files on disk")]
+    Verify {
+        keys: Vec<String>,
+    },
+    #[command(about = "Prints how many entries each cached table has per distance")]
+    Stats {
+        keys: Vec<String>,
+    },
+    #[command(about = "Deletes cached tables. Deletes all table files if no keys are given")]
+    Clean {
+        keys: Vec<String>,
+    },
 }
 
 impl Cli {
@@ -46,79 +145,10 @@ impl Cli {
     }
 
     pub fn parse_step_configs(&self) -> Result<Vec<StepConfig>, String> {
-        let step_name_regex = Regex::new("[A-Za-z0-9_-]").unwrap();
-        let default_niss_type = self.get_default_niss_type();
-        self.steps.split(">")
-            .map(|step| step.trim())
-            .map(|step| {
-                let param_start = step.find("[");
-                if param_start.is_none() {
-                    if !step_name_regex.is_match(step) {
-                        return Err(format!("Invalid step name {}", step));
-                    }
-                    return Ok(StepConfig {
-                        kind: StepKind::from_str(step)?,
-                        substeps: None,
-                        min: None,
-                        max: None,
-                        absolute_min: None,
-                        absolute_max: None,
-                        niss: default_niss_type,
-                        step_limit: None,
-                        quality: self.quality,
-                        params: HashMap::new()
-                    });
-                } else {
-                    if !step.ends_with("]") {
-                        return Err(format!("Expected step parameters to end with ] {}", step));
-                    }
-                    let param_start = param_start.unwrap();
-                    let name = &step[0..param_start];
-                    let mut step_prototype = StepConfig {
-                        kind: StepKind::from_str(name)?,
-                        substeps: None,
-                        min: None,
-                        max: None,
-                        absolute_min: None,
-                        absolute_max: None,
-                        niss: default_niss_type,
-                        step_limit: None,
-                        quality: self.quality,
-                        params: HashMap::new()
-                    };
-                    let params: Vec<&str> = (&step[(param_start + 1)..(step.len() - 1)]).split(";").collect();
-                    for param in params {
-                        if param.contains("=") {
-                            let parts: Vec<&str> = param.split("=").collect();
-                            if parts.len() != 2 {
-                                return Err(format!("Invalid param format {}", param));
-                            }
-                            match parts[0] {
-                                "limit" => step_prototype.step_limit = Some(usize::from_str(parts[1]).map_err(|x| format!("Unable to parse value '{}' for count. '{x}'", parts[1]))?),
-                                key @ "min" | key @ "min-rel" => step_prototype.min = Some(u8::from_str(parts[1]).map_err(|x| format!("Unable to parse value '{}' for {key}. '{x}'", parts[1]))?),
-                                key @ "max" | key @ "max-rel" => step_prototype.max = Some(u8::from_str(parts[1]).map_err(|x| format!("Unable to parse value '{}' for {key}. '{x}'", parts[1]))?),
-                                "min-abs" => step_prototype.absolute_min = Some(u8::from_str(parts[1]).map_err(|x| format!("Unable to parse value '{}' for min-abs. '{x}'", parts[1]))?),
-                                "max-abs" => step_prototype.absolute_max = Some(u8::from_str(parts[1]).map_err(|x| format!("Unable to parse value '{}' for max-abs. '{x}'", parts[1]))?),
-                                "niss" => step_prototype.niss = Some(match parts[1] {
-                                    "always" | "true" => NissSwitchType::Always,
-                                    "before" => NissSwitchType::Before,
-                                    "none" | "never" | "false" => NissSwitchType::Never,
-                                    x => Err(format!("Invalid NISS type {x}. Expected one of 'always', 'before', 'none'"))?
-                                }),
-                                key => {
-                                    step_prototype.params.insert(key.to_string(), parts[1].to_string());
-                                }
-                            }
-                        } else {
-                            step_prototype.substeps = Some(step_prototype.substeps.map_or(vec![param.to_string()], |mut v|{
-                                v.push(param.to_string());
-                                v
-                            }));
-                        }
-                    }
-                    Ok(step_prototype)
-                }
-            })
-            .collect()
+        let pipeline = Pipeline::from_str(self.steps.as_str())?;
+        Ok(pipeline
+            .with_default_niss(self.get_default_niss_type())
+            .with_quality(self.quality)
+            .into())
     }
 }
\ No newline at end of file
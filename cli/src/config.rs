@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use serde::Deserialize;
+
+use crate::cli::Cli;
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+//Every option that is set overrides the default of the corresponding CLI flag
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub steps: Option<String>,
+    pub quality: Option<usize>,
+    pub niss: Option<bool>,
+    pub solutions: Option<usize>,
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+    pub all: Option<bool>,
+}
+
+pub fn default_path() -> PathBuf {
+    let mut path = home::home_dir().unwrap();
+    path.push(".cubelib");
+    path.push("config.toml");
+    path
+}
+
+pub fn load(path: &PathBuf) -> Result<Config, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Unable to read config file {}: {e}", path.display()))?;
+    toml::from_str(content.as_str()).map_err(|e| format!("Invalid config file {}: {e}", path.display()))
+}
+
+//Applies the selected profile to all options that weren't passed on the command line
+pub fn apply_profile(cli: &mut Cli, matches: &ArgMatches) -> Result<(), String> {
+    let name = match cli.profile.as_ref() {
+        Some(name) => name.clone(),
+        None => return Ok(()),
+    };
+    let path = cli.config.clone().unwrap_or_else(default_path);
+    let mut config = load(&path)?;
+    let profile = config.profiles.remove(name.as_str())
+        .ok_or_else(|| if config.profiles.is_empty() {
+            format!("Unknown profile {name}. {} doesn't define any profiles", path.display())
+        } else {
+            format!("Unknown profile {name}. Expected one of {}", config.profiles.keys().cloned().collect::<Vec<String>>().join(", "))
+        })?;
+
+    let from_profile = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+    //Boolean flags can be turned off with their --no- variant, which also takes precedence over the profile
+    let flag_from_profile = |id: &str, negated: &str| from_profile(id) && from_profile(negated);
+    if let Some(steps) = profile.steps.filter(|_| from_profile("steps")) {
+        cli.steps = steps;
+    }
+    if let Some(quality) = profile.quality.filter(|_| from_profile("quality")) {
+        cli.quality = quality;
+    }
+    if let Some(niss) = profile.niss.filter(|_| flag_from_profile("niss", "no_niss")) {
+        cli.niss = niss;
+    }
+    if let Some(solutions) = profile.solutions.filter(|_| from_profile("solution_count")) {
+        cli.solution_count = Some(solutions);
+    }
+    if let Some(min) = profile.min.filter(|_| from_profile("min")) {
+        cli.min = min;
+    }
+    if let Some(max) = profile.max.filter(|_| from_profile("max")) {
+        cli.max = Some(max);
+    }
+    if let Some(all) = profile.all.filter(|_| flag_from_profile("all_solutions", "no_all_solutions")) {
+        cli.all_solutions = all;
+    }
+    Ok(())
+}
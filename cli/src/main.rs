@@ -1,24 +1,36 @@
 use std::str::FromStr;
 use std::time::Instant;
 
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use cubelib::algs::Algorithm;
 use cubelib::defs::StepKind;
-use cubelib::puzzles::c333::{Cube333, Turn333};
+use cubelib::puzzles::c333::{Cube333, Transformation333, Turn333};
 use cubelib::puzzles::c333::steps::{eo, solver};
 use cubelib::puzzles::c333::steps::tables::PruningTables333;
 use cubelib::puzzles::puzzle::ApplyAlgorithm;
 use cubelib::solver::{CancellationToken, stream};
+use cubelib::solver::moveset::TransitionTable333;
 use cubelib::solver::solution::Solution;
+use cubelib::steps::step::{DefaultStepOptions, Step};
 use log::{debug, error, info, LevelFilter};
 use simple_logger::SimpleLogger;
 
-use crate::cli::Cli;
+use crate::cli::{Cli, Commands};
+use crate::output::{OutputFormat, SolutionOutput};
 
+mod analyze;
+mod batch;
 mod cli;
+mod config;
+mod output;
+mod render;
+mod repl;
+mod tables;
+mod verify;
 
 fn main() {
-    let cli: Cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli: Cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
     SimpleLogger::new()
         .with_level(if cli.verbose {
             LevelFilter::Trace
@@ -30,8 +42,28 @@ fn main() {
         .init()
         .unwrap();
 
+    if let Err(e) = config::apply_profile(&mut cli, &matches) {
+        error!("{e}");
+        std::process::exit(1);
+    }
+
+    if let Some(command) = &cli.command {
+        let result = match command {
+            Commands::Tables { command } => tables::run(command),
+            Commands::Batch(args) => batch::run(&cli, args),
+            Commands::Analyze { format, scramble } => analyze::run(scramble, *format),
+            Commands::Verify { format, scramble, solution } => verify::run(scramble, solution.as_deref(), *format),
+            Commands::Render { style, step, mask, scramble, solution } => render::run(scramble, solution.as_deref(), *step, *style, mask.as_deref()),
+            Commands::Repl => repl::run(&cli),
+        };
+        if let Err(e) = result {
+            error!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let scramble = Algorithm::from_str(cli.scramble.as_str()).expect("Invalid scramble {}");
+    let scramble = Algorithm::from_str(cli.scramble.as_ref().unwrap().as_str()).expect("Invalid scramble {}");
     let mut cube = Cube333::default();
     cube.apply_alg(&scramble);
 
@@ -58,14 +90,52 @@ fn main() {
     };
 
 
-    let solutions = cubelib::solver::solve_steps(cube, &steps, CancellationToken::new());
-
     info!("Generating solutions\n");
     let time = Instant::now();
 
+    let solutions = solve(&cli, cube, &steps);
+
+    //The iterator is always sorted, so this just prints the shortest solutions
+    match cli.format {
+        OutputFormat::Text => for solution in solutions {
+            print_solution(&cli, solution);
+        },
+        OutputFormat::Json => {
+            let solutions: Vec<SolutionOutput> = solutions
+                .map(|solution| SolutionOutput::new(&scramble, &solution))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&solutions).unwrap());
+        },
+        OutputFormat::Ndjson => for solution in solutions {
+            println!("{}", serde_json::to_string(&SolutionOutput::new(&scramble, &solution)).unwrap());
+        },
+    }
+
+    debug!("Took {}ms", time.elapsed().as_millis());
+}
+
+fn print_solution(cli: &Cli, solution: Solution<Turn333>) {
+    if cli.compact_solutions {
+        if cli.plain_solution {
+            println!("{}", Into::<Algorithm<Turn333>>::into(solution));
+        } else {
+            let alg = Into::<Algorithm<Turn333>>::into(solution);
+            println!("{alg} ({})", alg.len());
+        }
+    } else {
+        println!("{}", solution);
+    }
+}
+
+type Steps333<'a> = Vec<(Step<'a, Turn333, Transformation333, Cube333, TransitionTable333>, DefaultStepOptions)>;
+
+//Applies the global filters of the CLI to the solutions of the given steps
+fn solve<'a>(cli: &'a Cli, cube: Cube333, steps: &'a Steps333<'a>) -> Box<dyn Iterator<Item = Solution<Turn333>> + 'a> {
+    let solutions = cubelib::solver::solve_steps(cube, steps, CancellationToken::new());
+
     let mut solutions: Box<dyn Iterator<Item = Solution<Turn333>>> = Box::new(solutions
         .skip_while(|alg| alg.len() < cli.min)
-        .take_while(|alg| cli.max.map_or(true, |max| alg.len() <= max)));
+        .take_while(|alg| cli.max.is_none_or(|max| alg.len() <= max)));
 
     // For e.g. FR the direction of the last move always matters, so we can't filter if we're doing FR
     let can_filter_last_move = steps.last().map(|(s, _)| s.kind() != StepKind::FR && s.kind() != StepKind::FIN).unwrap_or(true);
@@ -83,20 +153,5 @@ fn main() {
         solutions = Box::new(solutions
             .take(cli.solution_count.unwrap_or(1)))
     }
-
-    //The iterator is always sorted, so this just prints the shortest solutions
-    for solution in solutions {
-        if cli.compact_solutions {
-            if cli.plain_solution {
-                println!("{}", Into::<Algorithm<Turn333>>::into(solution));
-            } else {
-                let alg = Into::<Algorithm<Turn333>>::into(solution);
-                println!("{alg} ({})", alg.len());
-            }
-        } else {
-            println!("{}", solution);
-        }
-    }
-
-    debug!("Took {}ms", time.elapsed().as_millis());
+    solutions
 }
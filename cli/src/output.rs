@@ -0,0 +1,77 @@
+use clap::ValueEnum;
+use cubelib::algs::Algorithm;
+use cubelib::defs::StepKind;
+use cubelib::puzzles::c333::Turn333;
+use cubelib::solver::solution::{Solution, SolutionStep};
+use serde::Serialize;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    //A single JSON array containing all solutions, printed after the search finished
+    Json,
+    //One JSON object per line, printed as soon as a solution is found
+    Ndjson,
+}
+
+#[derive(Serialize)]
+pub struct SolutionOutput {
+    pub scramble: String,
+    pub steps: Vec<StepOutput>,
+    pub solution: String,
+    pub length: usize,
+}
+
+#[derive(Serialize)]
+pub struct StepOutput {
+    pub kind: String,
+    pub variant: String,
+    pub normal: String,
+    pub inverse: String,
+    pub length: usize,
+    pub total_length: usize,
+}
+
+impl SolutionOutput {
+    pub fn new(scramble: &Algorithm<Turn333>, solution: &Solution<Turn333>) -> SolutionOutput {
+        let mut total_length = 0;
+        let steps = solution.clone().compact().steps.iter()
+            .map(|step| {
+                total_length += step.alg.len();
+                StepOutput::new(step, total_length)
+            })
+            .collect();
+        //Same as the text output. Only a solution that finishes the cube can be written without NISS
+        let final_alg: Algorithm<Turn333> = if solution.steps.last().map(|x| x.kind == StepKind::FIN).unwrap_or(false) {
+            Into::<Algorithm<Turn333>>::into(solution.clone()).to_uninverted()
+        } else {
+            solution.clone().into()
+        };
+        SolutionOutput {
+            scramble: scramble.to_string(),
+            steps,
+            solution: final_alg.to_string(),
+            length: final_alg.len(),
+        }
+    }
+}
+
+impl StepOutput {
+    pub fn new(step: &SolutionStep<Turn333>, total_length: usize) -> StepOutput {
+        StepOutput {
+            kind: step.kind.to_string(),
+            variant: step.variant.clone(),
+            normal: moves_to_string(&step.alg.normal_moves),
+            inverse: moves_to_string(&step.alg.inverse_moves),
+            length: step.alg.len(),
+            total_length,
+        }
+    }
+}
+
+fn moves_to_string(moves: &[Turn333]) -> String {
+    moves.iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
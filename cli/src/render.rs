@@ -0,0 +1,64 @@
+use std::io::Read;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use cubelib::algs::Algorithm;
+use cubelib::puzzles::c333::{Cube333, Turn333};
+use cubelib::puzzles::c333::mask::Mask;
+use cubelib::puzzles::c333::render;
+use cubelib::puzzles::c333::steps::verify;
+use cubelib::puzzles::puzzle::ApplyAlgorithm;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RenderStyle {
+    //Unfolded net with colored blocks
+    Ansi,
+    //Unfolded net with the letter of each sticker's face
+    Net,
+    //All 54 stickers in URFDLB order on a single line
+    Facelets,
+    Svg,
+    Isometric,
+}
+
+pub fn run(scramble: &str, solution: Option<&str>, step: Option<usize>, style: RenderStyle, mask: Option<&str>) -> Result<(), String> {
+    let mask = mask.map_or(Ok(Mask::all()), Mask::from_str)?;
+    let scramble = Algorithm::<Turn333>::from_str(scramble).map_err(|_| format!("Invalid scramble {scramble}"))?;
+    let mut cube = Cube333::default();
+    cube.apply_alg(&scramble);
+
+    let solution = match solution {
+        Some("-") => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).map_err(|e| format!("Unable to read stdin: {e}"))?;
+            Some(input)
+        },
+        solution => solution.map(|s| s.to_string()),
+    };
+    if let Some(solution) = solution {
+        let solution = verify::parse_solution(solution.as_str())?;
+        let step_count = step.unwrap_or(solution.steps.len());
+        if step_count > solution.steps.len() {
+            return Err(format!("The solution only has {} steps", solution.steps.len()));
+        }
+        for step in solution.steps.iter().take(step_count) {
+            cube.apply_alg(&step.alg);
+        }
+    } else if step.is_some() {
+        return Err("--step requires a solution".to_string());
+    }
+
+    println!("{}", render_cube(&cube, style, &mask));
+    Ok(())
+}
+
+pub fn render_cube(cube: &Cube333, style: RenderStyle, mask: &Mask) -> String {
+    let facelets = mask.apply(cube);
+    match style {
+        RenderStyle::Ansi => render::ansi_net(&facelets),
+        RenderStyle::Net => render::letter_net(&facelets),
+        RenderStyle::Facelets => render::facelet_string(&facelets),
+        RenderStyle::Svg => render::svg_net(&facelets),
+        RenderStyle::Isometric => render::svg_isometric(&facelets),
+    }
+}
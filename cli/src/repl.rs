@@ -0,0 +1,210 @@
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use std::time::Instant;
+
+use clap::Parser;
+use cubelib::algs::Algorithm;
+use cubelib::defs::StepKind;
+use cubelib::puzzles::c333::{Cube333, Turn333};
+use cubelib::puzzles::c333::mask::Mask;
+use cubelib::puzzles::c333::steps::{analysis, solver};
+use cubelib::puzzles::c333::steps::tables::PruningTables333;
+use cubelib::puzzles::puzzle::ApplyAlgorithm;
+use cubelib::solver::solution::{ApplySolution, Solution, SolutionStep};
+use cubelib::steps::pipeline::Pipeline;
+use log::{debug, error};
+
+use crate::cli::Cli;
+use crate::output::OutputFormat;
+use crate::render::RenderStyle;
+
+const HELP: &str = "Commands:
+  scramble <alg>       Sets a new scramble and clears all applied moves
+  steps <pipeline>     Sets the steps used by solve, e.g. steps EO > DR[triggers=R] > HTR
+  solve [options]      Solves the current state. Accepts -n, -m, -M, -q, -N, -a and -c like the CLI
+  apply <alg>          Applies moves to the current state. Moves in parentheses are applied on the inverse
+  show [mask]          Prints the scramble, the applied moves and the current cube. The mask greys out stickers, e.g. show eo-fb
+  analyze              Prints the analysis of the current state
+  undo                 Reverts the last scramble or apply
+  help                 Prints this message
+  exit                 Ends the session";
+
+#[derive(Parser)]
+#[command(name = "solve", no_binary_name = true, disable_help_flag = true)]
+struct SolveArgs {
+    #[arg(short = 'n')]
+    solution_count: Option<usize>,
+    #[arg(short = 'm', long = "min")]
+    min: Option<usize>,
+    #[arg(short = 'M', long = "max")]
+    max: Option<usize>,
+    #[arg(short = 'q', long = "quality")]
+    quality: Option<usize>,
+    #[arg(short = 'N', long = "niss", default_value_t = false)]
+    niss: bool,
+    #[arg(short = 'a', long = "all", default_value_t = false)]
+    all_solutions: bool,
+    #[arg(short = 'c', long = "compact", default_value_t = false)]
+    compact_solutions: bool,
+}
+
+#[derive(Clone)]
+struct State {
+    scramble: Algorithm<Turn333>,
+    //Everything that was applied after the scramble, one step per apply command
+    applied: Solution<Turn333>,
+}
+
+impl State {
+    fn cube(&self) -> Cube333 {
+        let mut cube = Cube333::default();
+        cube.apply_alg(&self.scramble);
+        cube.apply_solution(&self.applied);
+        cube
+    }
+}
+
+struct Repl {
+    //The global options, with the steps replaced by the steps command
+    cli: Cli,
+    tables: PruningTables333,
+    state: State,
+    history: Vec<State>,
+}
+
+pub fn run(cli: &Cli) -> Result<(), String> {
+    let mut repl = Repl {
+        cli: cli.clone(),
+        tables: PruningTables333::new(),
+        state: State {
+            scramble: Algorithm::new(),
+            applied: Solution::new(),
+        },
+        history: vec![],
+    };
+    if let Some(scramble) = cli.scramble.as_ref() {
+        repl.set_scramble(scramble)?;
+    }
+    println!("Type help for a list of commands");
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        std::io::stdout().flush().map_err(|e| e.to_string())?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| e.to_string())?,
+            None => break,
+        };
+        let (command, args) = line.trim().split_once(char::is_whitespace).unwrap_or((line.trim(), ""));
+        let args = args.trim();
+        let result = match command {
+            "" => Ok(()),
+            "scramble" => repl.set_scramble(args),
+            "steps" => repl.set_steps(args),
+            "solve" => repl.solve(args),
+            "apply" => repl.apply(args),
+            "show" => repl.show(args),
+            "analyze" => repl.analyze(),
+            "undo" => repl.undo(),
+            "help" => {
+                println!("{HELP}");
+                Ok(())
+            },
+            "exit" | "quit" => break,
+            x => Err(format!("Unknown command {x}. Type help for a list of commands")),
+        };
+        if let Err(e) = result {
+            error!("{e}");
+        }
+    }
+    Ok(())
+}
+
+impl Repl {
+    fn set_scramble(&mut self, scramble: &str) -> Result<(), String> {
+        let scramble = Algorithm::<Turn333>::from_str(scramble).map_err(|_| format!("Invalid scramble {scramble}"))?;
+        self.history.push(self.state.clone());
+        self.state = State {
+            scramble,
+            applied: Solution::new(),
+        };
+        Ok(())
+    }
+
+    //Builds the steps once, so invalid parameters are reported right away and the tables are ready for solve
+    fn set_steps(&mut self, steps: &str) -> Result<(), String> {
+        let pipeline = Pipeline::from_str(steps)?;
+        let mut cli = self.cli.clone();
+        cli.steps = pipeline.to_string();
+        let configs = cli.parse_step_configs()?;
+        solver::gen_tables(&configs, &mut self.tables);
+        solver::build_steps(configs, &self.tables)?;
+        self.cli = cli;
+        println!("Steps: {pipeline}");
+        Ok(())
+    }
+
+    fn apply(&mut self, alg: &str) -> Result<(), String> {
+        let alg = Algorithm::<Turn333>::from_str(alg).map_err(|_| format!("Invalid algorithm {alg}"))?;
+        self.history.push(self.state.clone());
+        self.state.applied.add_step(SolutionStep {
+            kind: StepKind::Other(String::new()),
+            variant: String::new(),
+            alg,
+        });
+        println!("Subgroup: {}", analysis::subgroup(&self.state.cube()));
+        Ok(())
+    }
+
+    fn undo(&mut self) -> Result<(), String> {
+        self.state = self.history.pop().ok_or("Nothing to undo")?;
+        Ok(())
+    }
+
+    fn solve(&mut self, args: &str) -> Result<(), String> {
+        let args = SolveArgs::try_parse_from(args.split_whitespace()).map_err(|e| e.to_string())?;
+        let mut cli = self.cli.clone();
+        cli.solution_count = args.solution_count.or(cli.solution_count);
+        cli.min = args.min.unwrap_or(cli.min);
+        cli.max = args.max.or(cli.max);
+        cli.quality = args.quality.unwrap_or(cli.quality);
+        cli.niss |= args.niss;
+        cli.all_solutions |= args.all_solutions;
+        cli.compact_solutions |= args.compact_solutions;
+
+        let configs = cli.parse_step_configs()?;
+        solver::gen_tables(&configs, &mut self.tables);
+        let steps = solver::build_steps(configs, &self.tables)?;
+        let time = Instant::now();
+        let mut found = false;
+        for solution in crate::solve(&cli, self.state.cube(), &steps) {
+            found = true;
+            crate::print_solution(&cli, solution);
+        }
+        if !found {
+            println!("No solution found");
+        }
+        debug!("Took {}ms", time.elapsed().as_millis());
+        Ok(())
+    }
+
+    fn show(&self, mask: &str) -> Result<(), String> {
+        let mask = if mask.is_empty() { Mask::all() } else { Mask::from_str(mask)? };
+        println!("Scramble: {}", self.state.scramble);
+        let applied: Algorithm<Turn333> = self.state.applied.clone().into();
+        println!("Applied: {} ({})", applied, applied.len());
+        println!("Steps: {}", self.cli.steps);
+        let cube = self.state.cube();
+        println!("Subgroup: {}", analysis::subgroup(&cube));
+        println!("{}", crate::render::render_cube(&cube, RenderStyle::Ansi, &mask));
+        Ok(())
+    }
+
+    fn analyze(&mut self) -> Result<(), String> {
+        for key in self.tables.keys() {
+            self.tables.gen(key)?;
+        }
+        crate::analyze::print(&analysis::analyze(&self.state.cube(), &self.tables), OutputFormat::Text)
+    }
+}
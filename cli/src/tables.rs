@@ -0,0 +1,166 @@
+use std::fs;
+
+use cubelib::puzzles::c333::steps::tables::PruningTables333;
+use cubelib::solver::lookup_table;
+use cubelib::solver::table_registry::TableRegistry;
+use log::info;
+
+use crate::cli::TablesCommand;
+
+pub fn run(command: &TablesCommand) -> Result<(), String> {
+    let tables = PruningTables333::new();
+    let registry = tables.registry();
+    match command {
+        TablesCommand::Generate { all, force, keys } => {
+            let keys = if *all {
+                registry.keys()
+            } else {
+                select_keys(registry, keys)?
+            };
+            generate(registry, keys, *force)
+        },
+        TablesCommand::List => {
+            list(registry);
+            Ok(())
+        },
+        TablesCommand::Verify { keys } => verify(registry, cached_keys(registry, keys)?),
+        TablesCommand::Stats { keys } => stats(registry, cached_keys(registry, keys)?),
+        TablesCommand::Clean { keys } => clean(registry, keys),
+    }
+}
+
+fn select_keys<'a>(registry: &'a TableRegistry, keys: &'a [String]) -> Result<Vec<&'a str>, String> {
+    for key in keys {
+        if !registry.contains(key) {
+            return Err(format!("Unknown table {key}. Expected one of {}", registry.keys().join(", ")));
+        }
+    }
+    Ok(keys.iter().map(|k| k.as_str()).collect())
+}
+
+//Without explicit keys, commands that read tables use every registered table that is cached or embedded
+fn cached_keys<'a>(registry: &'a TableRegistry, keys: &'a [String]) -> Result<Vec<&'a str>, String> {
+    if keys.is_empty() {
+        Ok(registry.keys().into_iter()
+            .filter(|key| registry.is_embedded(key) || registry.path(key).exists())
+            .collect())
+    } else {
+        select_keys(registry, keys)
+    }
+}
+
+fn read_table(registry: &TableRegistry, key: &str) -> Result<Vec<u8>, String> {
+    if let Some(data) = registry.embedded_bytes(key) {
+        Ok(data.to_vec())
+    } else {
+        registry.read_bytes(key).map_err(|e| format!("Error loading {key} table from disk: {e}"))
+    }
+}
+
+fn generate(registry: &TableRegistry, keys: Vec<&str>, force: bool) -> Result<(), String> {
+    for key in keys {
+        if registry.is_embedded(key) {
+            info!("Skipping {key} table, it is built into the binary");
+        } else if force {
+            let data = registry.generate_bytes(key)?;
+            registry.write_bytes(key, data).map_err(|e| format!("Error saving {key} table: {e}"))?;
+            info!("Saved {key} table to disk");
+        } else {
+            registry.gen(key)?;
+        }
+    }
+    Ok(())
+}
+
+fn list(registry: &TableRegistry) {
+    println!("Tables in {}", registry.dir().display());
+    for key in registry.keys() {
+        if let Some(data) = registry.embedded_bytes(key) {
+            println!("{key:<12} {:>10}  embedded", format_size(data.len()));
+            continue;
+        }
+        match registry.read_bytes(key) {
+            Ok(data) => {
+                let version = match lookup_table::table_stats(&data) {
+                    Ok(stats) if lookup_table::is_current_version(&data) => format!("v{} {:?}", stats.version, stats.table_type),
+                    Ok(stats) => format!("v{} {:?} (outdated)", stats.version, stats.table_type),
+                    Err(e) => format!("invalid: {e}"),
+                };
+                println!("{key:<12} {:>10}  {version}", format_size(data.len()));
+            },
+            Err(_) => println!("{key:<12} {:>10}", "not cached"),
+        }
+    }
+}
+
+fn verify(registry: &TableRegistry, keys: Vec<&str>) -> Result<(), String> {
+    let mut failed = vec![];
+    for key in keys {
+        let cached = read_table(registry, key)?;
+        let expected = registry.generate_bytes(key)?;
+        if cached == expected {
+            println!("{key:<12} OK");
+        } else {
+            let differences = cached.iter().zip(expected.iter())
+                .filter(|(a, b)| a != b)
+                .count() + cached.len().abs_diff(expected.len());
+            println!("{key:<12} MISMATCH ({differences} bytes differ)");
+            failed.push(key);
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Tables don't match their generated version: {}", failed.join(", ")))
+    }
+}
+
+fn stats(registry: &TableRegistry, keys: Vec<&str>) -> Result<(), String> {
+    for key in keys {
+        let data = read_table(registry, key)?;
+        let stats = lookup_table::table_stats(&data).map_err(|e| format!("Invalid {key} table: {e}"))?;
+        let total: usize = stats.distribution.values().sum::<usize>() + stats.unreached;
+        println!("{key} ({:?}, {total} entries)", stats.table_type);
+        for (value, count) in stats.distribution.iter() {
+            println!("  {value:>3}: {count:>10} {:>7.3}%", *count as f64 * 100.0 / total as f64);
+        }
+        if stats.unreached > 0 {
+            println!("  unreached: {:>10}", stats.unreached);
+        }
+    }
+    Ok(())
+}
+
+fn clean(registry: &TableRegistry, keys: &[String]) -> Result<(), String> {
+    let paths = if keys.is_empty() {
+        let dir = registry.dir();
+        match fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "tbl"))
+                .collect(),
+            Err(_) => vec![],
+        }
+    } else {
+        select_keys(registry, keys)?.into_iter()
+            .map(|key| registry.path(key))
+            .filter(|p| p.exists())
+            .collect::<Vec<_>>()
+    };
+    for path in paths {
+        fs::remove_file(&path).map_err(|e| format!("Error deleting {}: {e}", path.display()))?;
+        println!("Deleted {}", path.display());
+    }
+    Ok(())
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1 << 20 {
+        format!("{:.2} MiB", bytes as f64 / (1 << 20) as f64)
+    } else if bytes >= 1 << 10 {
+        format!("{:.1} KiB", bytes as f64 / (1 << 10) as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
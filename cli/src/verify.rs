@@ -0,0 +1,99 @@
+use std::io::Read;
+use std::str::FromStr;
+
+use cubelib::algs::Algorithm;
+use cubelib::puzzles::c333::Turn333;
+use cubelib::puzzles::c333::steps::verify::{self, Verification};
+use serde::Serialize;
+
+use crate::output::{OutputFormat, StepOutput};
+
+#[derive(Serialize)]
+struct VerificationOutput {
+    solved: bool,
+    length: usize,
+    cancelled: String,
+    cancelled_length: usize,
+    steps: Vec<VerifiedStepOutput>,
+    subgroup: String,
+    unsolved_edges: Vec<String>,
+    unsolved_corners: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct VerifiedStepOutput {
+    #[serde(flatten)]
+    step: StepOutput,
+    subgroup: String,
+}
+
+impl From<&Verification> for VerificationOutput {
+    fn from(value: &Verification) -> Self {
+        VerificationOutput {
+            solved: value.solved,
+            length: value.length,
+            cancelled: value.cancelled.to_string(),
+            cancelled_length: value.cancelled_length,
+            steps: value.steps.iter()
+                .map(|s| VerifiedStepOutput {
+                    step: StepOutput::new(&s.step, s.total_length),
+                    subgroup: s.subgroup.to_string(),
+                })
+                .collect(),
+            subgroup: value.subgroup.to_string(),
+            unsolved_edges: value.unsolved_edges.clone(),
+            unsolved_corners: value.unsolved_corners.clone(),
+        }
+    }
+}
+
+pub fn run(scramble: &str, solution: Option<&str>, format: OutputFormat) -> Result<(), String> {
+    let scramble = Algorithm::<Turn333>::from_str(scramble).map_err(|_| format!("Invalid scramble {scramble}"))?;
+    let solution = match solution {
+        Some(solution) => solution.to_string(),
+        None => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input).map_err(|e| format!("Unable to read stdin: {e}"))?;
+            input
+        }
+    };
+    let solution = verify::parse_solution(solution.as_str())?;
+    let verification = verify::verify(&scramble, &solution);
+
+    match format {
+        OutputFormat::Text => print_text(&verification),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&VerificationOutput::from(&verification)).map_err(|e| e.to_string())?),
+        OutputFormat::Ndjson => println!("{}", serde_json::to_string(&VerificationOutput::from(&verification)).map_err(|e| e.to_string())?),
+    }
+    if verification.solved {
+        Ok(())
+    } else {
+        Err("The solution doesn't solve the scramble".to_string())
+    }
+}
+
+fn print_text(verification: &Verification) {
+    let longest_alg_length = verification.steps.iter()
+        .map(|s| s.step.alg.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for step in verification.steps.iter() {
+        let name = match &step.step.kind {
+            cubelib::defs::StepKind::Other(kind) if kind.is_empty() => String::new(),
+            kind if step.step.variant.is_empty() => kind.to_string(),
+            kind => format!("{kind}-{}", step.step.variant),
+        };
+        println!("{:longest_alg_length$}  //{name} ({}/{}) -> {}", step.step.alg.to_string(), step.length, step.total_length, step.subgroup);
+    }
+    println!();
+    if verification.solved {
+        println!("Solved ({})", verification.length);
+    } else {
+        println!("Not solved ({}). Subgroup: {}", verification.length, verification.subgroup);
+        println!("Unsolved edges: {}", verification.unsolved_edges.join(" "));
+        println!("Unsolved corners: {}", verification.unsolved_corners.join(" "));
+    }
+    if verification.cancelled_length != verification.length {
+        println!("After cancellation ({}): {}", verification.cancelled_length, verification.cancelled);
+    }
+}
@@ -1,4 +1,5 @@
-use crate::puzzles::c333::{CornerCube333, Cube333};
+use crate::puzzles::c333::{CornerCube333, Cube333, Transformation333};
+use crate::puzzles::puzzle::TransformableMut;
 
 pub trait COCountUD {
     fn co_count(&self) -> u8;
@@ -35,6 +36,23 @@ impl COCountUD for Cube333 {
     }
 }
 
+//Counting on the other axes rotates that axis to UD first
+impl COCountFB for Cube333 {
+    fn co_count(&self) -> u8 {
+        let mut cube = self.corners;
+        cube.transform(Transformation333::X);
+        COCountUD::co_count(&cube)
+    }
+}
+
+impl COCountLR for Cube333 {
+    fn co_count(&self) -> u8 {
+        let mut cube = self.corners;
+        cube.transform(Transformation333::Z);
+        COCountUD::co_count(&cube)
+    }
+}
+
 impl COCountUD for CornerCube333 {
 
     #[cfg(target_feature = "avx2")]
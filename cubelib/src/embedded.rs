@@ -0,0 +1,11 @@
+//Tables for the embedded_tables feature. The files are regular table files named after their key, see the README on how
+//to regenerate them
+
+#[cfg(feature = "333eo")]
+pub const EO: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tables/333/eo.tbl"));
+#[cfg(feature = "333fr")]
+pub const FR: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tables/333/fr.tbl"));
+#[cfg(feature = "333fr")]
+pub const FRLS: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tables/333/frls.tbl"));
+#[cfg(feature = "333finish")]
+pub const FR_FINISH: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tables/333/frfin.tbl"));
@@ -15,4 +15,6 @@ pub mod steps;
 pub mod solver;
 #[cfg(target_arch = "wasm32")]
 mod wasm_util;
+#[cfg(feature = "embedded_tables")]
+mod embedded;
 pub mod puzzles;
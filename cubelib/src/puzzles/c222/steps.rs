@@ -37,10 +37,13 @@ pub mod tables {
     use crate::puzzles::c222::steps::finish::{DIRECT_FINISH_MOVESET, DirectFinishPruningTable};
     use crate::solver::lookup_table;
     use crate::solver::lookup_table::TableType;
+    use crate::solver::table_registry::TableRegistry;
 
+    pub const FIN_KEY: &str = "fin";
+
+    #[derive(Clone)]
     pub struct PruningTables222 {
-        #[cfg(feature = "222finish")]
-        direct_finish: Option<DirectFinishPruningTable>
+        registry: TableRegistry,
     }
 
     impl PruningTables222 {
@@ -48,32 +51,35 @@ pub mod tables {
         pub const VERSION: u32 = 1;
 
         pub fn new() -> PruningTables222 {
+            let mut registry = TableRegistry::new("222");
+            #[cfg(feature = "222finish")]
+            registry.register(FIN_KEY, gen_direct_finish);
             PruningTables222 {
-                #[cfg(feature = "222finish")]
-                direct_finish: None
+                registry
             }
         }
 
-        pub fn load(&mut self, key: &str, data: Box<Vec<u8>>) -> Result<(), String> {
-            match key {
-                #[cfg(feature = "222finish")]
-                "fin" => self.direct_finish = Some(DirectFinishPruningTable::load(data)?),
-                _ => {}
-            }
-            Ok(())
+        pub fn registry(&self) -> &TableRegistry {
+            &self.registry
+        }
+
+        pub fn registry_mut(&mut self) -> &mut TableRegistry {
+            &mut self.registry
+        }
+
+        #[cfg(feature = "fs")]
+        pub fn load(&self, key: &str, data: Box<Vec<u8>>) -> Result<(), String> {
+            self.registry.load_bytes(key, *data)
         }
 
         #[cfg(feature = "222finish")]
-        pub fn gen_fin(&mut self) {
-            if self.direct_finish.is_none() {
-                let table = gen_direct_finish();
-                self.direct_finish = Some(table);
-            }
+        pub fn gen_fin(&self) {
+            let _ = self.registry.gen(FIN_KEY);
         }
 
         #[cfg(feature = "222finish")]
         pub fn fin(&self) -> Option<&DirectFinishPruningTable> {
-            self.direct_finish.as_ref()
+            self.registry.get(FIN_KEY).ok()
         }
     }
 
@@ -1,13 +1,15 @@
 use std::fmt::{Display, Formatter};
 
 use crate::puzzles::c333::{CornerCube333, EdgeCube333, Transformation333, Turn333};
+use crate::puzzles::c333::mask::color_axis;
+use crate::puzzles::c333::render::{letter, Facelets};
 use crate::puzzles::cube::{CornerPosition, EdgePosition};
-use crate::puzzles::cube::{CubeColor, CubeOuterTurn};
+use crate::puzzles::cube::{CubeAxis, CubeColor, CubeFace, CubeOuterTurn};
 use crate::puzzles::cube::CornerPosition::*;
 use crate::puzzles::cube::CubeColor::*;
 use crate::puzzles::cube::CubeFace::*;
 use crate::puzzles::cube::EdgePosition::*;
-use crate::puzzles::puzzle::{InvertibleMut, TransformableMut, TurnableMut};
+use crate::puzzles::puzzle::{Invertible, InvertibleMut, TransformableMut, TurnableMut};
 
 //http://kociemba.org/math/cubielevel.htm
 #[derive(Debug, Clone, Copy, PartialEq, Hash)]
@@ -38,6 +40,24 @@ impl Cube333 {
     pub fn count_bad_edges(&self) -> (u8, u8, u8) {
         self.edges.count_bad_edges()
     }
+
+    //Conjugates the cube with the reflection that swaps the two faces on this axis. Mirroring a scrambled cube gives the
+    //same cube as applying the mirrored scramble, see Algorithm::mirror
+    pub fn mirror(&mut self, axis: CubeAxis) {
+        let rotation = match axis {
+            CubeAxis::UD => Some(Transformation333::Z),
+            CubeAxis::FB => Some(Transformation333::Y),
+            _ => Option::None,
+        };
+        if let Some(rotation) = rotation {
+            self.transform(rotation);
+        }
+        self.edges.mirror_lr();
+        self.corners.mirror_lr();
+        if let Some(rotation) = rotation {
+            self.transform(rotation.invert());
+        }
+    }
 }
 
 impl Display for Cube333 {
@@ -152,29 +172,110 @@ impl Cube333 {
         [Yellow, Orange],
     ];
 
+    const CENTER_COLORS: [CubeColor; 6] = [White, Yellow, Green, Blue, Orange, Red];
+
     pub fn get_facelets(&self) -> [[CubeColor; 9]; 6] {
+        self.map_facelets(
+            |id, twist| Cube333::CORNER_COLORS[id as usize][twist],
+            |id, flip| Cube333::EDGE_COLORS[id as usize][flip],
+            |face| Cube333::CENTER_COLORS[face as usize],
+        )
+    }
+
+    //Builds the cube shown by the facelets. Fails if the stickers don't belong to a cube in the standard orientation
+    //that can be reached by turning, e.g. because a corner is twisted or two pieces are swapped
+    pub fn from_facelets(facelets: &Facelets) -> Result<Cube333, String> {
+        for face in CubeFace::ALL {
+            if facelets[face][4] != Cube333::CENTER_COLORS[face as usize] {
+                return Err(format!("Expected {} in the center of {face:?}. Only cubes with white on top and green in front are supported", letter(Cube333::CENTER_COLORS[face as usize])));
+            }
+        }
+        if facelets.iter().flatten().any(|c| *c == CubeColor::None) {
+            return Err("The facelets contain hidden stickers".to_string());
+        }
+
+        //The solved cube tells which piece and sticker index every position shows
+        let ids = Cube333::default().get_sticker_ids();
+        let mut corner_stickers = [[White; 3]; 8];
+        let mut edge_stickers = [[White; 2]; 12];
+        for face in 0..6 {
+            for i in 0..9 {
+                let id = ids[face][i] as usize;
+                match id {
+                    0..=23 => corner_stickers[id / 3][id % 3] = facelets[face][i],
+                    24..=47 => edge_stickers[(id - 24) / 2][id % 2] = facelets[face][i],
+                    _ => {}
+                }
+            }
+        }
+
+        let mut corner_bytes = [0_u8; 16];
+        for (pos, stickers) in corner_stickers.iter().enumerate() {
+            let (id, orientation) = (0..8).flat_map(|id| (0..3).map(move |o| (id, o)))
+                .find(|(id, o)| (0..3).all(|t| Cube333::CORNER_COLORS[*id][(3 - o + t) % 3] == stickers[t]))
+                .ok_or_else(|| format!("No corner has the stickers {}", stickers.iter().map(|c| letter(*c)).collect::<String>()))?;
+            corner_bytes[pos] = (id as u8) << 5 | orientation as u8;
+        }
+
+        let mut edge_bytes = [0_u8; 16];
+        for (pos, stickers) in edge_stickers.iter().enumerate() {
+            let id = (0..12)
+                .find(|id| Cube333::EDGE_COLORS[*id] == *stickers || Cube333::EDGE_COLORS[*id] == [stickers[1], stickers[0]])
+                .ok_or_else(|| format!("No edge has the stickers {}", stickers.iter().map(|c| letter(*c)).collect::<String>()))?;
+            let bad = |axis: CubeAxis| {
+                let slot = Cube333::EDGE_COLORS[pos].iter().position(|c| *c == eo_reference(Cube333::EDGE_COLORS[pos], axis)).unwrap();
+                stickers[slot] != eo_reference(Cube333::EDGE_COLORS[id], axis)
+            };
+            edge_bytes[pos] = (id as u8) << 4 | (bad(CubeAxis::UD) as u8) << 3 | (bad(CubeAxis::FB) as u8) << 2 | (bad(CubeAxis::LR) as u8) << 1;
+        }
+
+        let corners = CornerCube333::from_bytes(corner_bytes)?;
+        let edges = EdgeCube333::from_bytes(edge_bytes)?;
+        if corner_bytes[0..8].iter().map(|b| (b & 0b11) as u32).sum::<u32>() % 3 != 0 {
+            return Err("A corner is twisted".to_string());
+        }
+        if edge_bytes[0..12].iter().map(|b| ((b >> 2) & 1) as u32).sum::<u32>() % 2 != 0 {
+            return Err("An edge is flipped".to_string());
+        }
+        if parity(&corner_bytes[0..8].iter().map(|b| b >> 5).collect::<Vec<u8>>()) != parity(&edge_bytes[0..12].iter().map(|b| b >> 4).collect::<Vec<u8>>()) {
+            return Err("Two pieces are swapped".to_string());
+        }
+        Ok(Cube333::new(edges, corners))
+    }
+
+    //Identifies every sticker independent of its position. Corner stickers are 0..24, edge stickers 24..48 and centers 48..54
+    pub fn get_sticker_ids(&self) -> [[u8; 9]; 6] {
+        self.map_facelets(
+            |id, twist| id * 3 + twist as u8,
+            |id, flip| 24 + id * 2 + flip as u8,
+            |face| 48 + face as u8,
+        )
+    }
+
+    //Calls corner with the id of the corner and the index of the sticker in CORNER_COLORS, edge with the id and index in EDGE_COLORS, and center with the face
+    fn map_facelets<T: Copy, C: Fn(u8, usize) -> T, E: Fn(u8, usize) -> T, M: Fn(CubeFace) -> T>(&self, corner: C, edge: E, center: M) -> [[T; 9]; 6] {
         let corners = self.corners.get_corners();
         let edges = self.edges.get_edges();
-        let mut facelets = [[None; 9]; 6];
+        let mut facelets = [[center(Up); 9]; 6];
 
         //There has to be a better way
         let c = |id: CornerPosition, twist: u8| {
-            let corner = corners[id as usize];
-            let twist_id = (3 - corner.orientation + twist) % 3;
-            Cube333::CORNER_COLORS[corner.id as usize][twist_id as usize]
+            let c = corners[id as usize];
+            let twist_id = (3 - c.orientation + twist) % 3;
+            corner(c.id, twist_id as usize)
         };
 
         let e = |id: EdgePosition, flip: bool| {
-            let edge = edges[id as usize];
-            let eo_id = !(edge.oriented_fb ^ flip) as usize;
-            Cube333::EDGE_COLORS[edge.id as usize][eo_id as usize]
+            let e = edges[id as usize];
+            let eo_id = !(e.oriented_fb ^ flip) as usize;
+            edge(e.id, eo_id)
         };
 
         facelets[Up][0] = c(UBL, 0);
         facelets[Up][1] = e(UB, false);
         facelets[Up][2] = c(UBR, 0);
         facelets[Up][3] = e(UL, false);
-        facelets[Up][4] = White;
+        facelets[Up][4] = center(Up);
         facelets[Up][5] = e(UR, false);
         facelets[Up][6] = c(UFL, 0);
         facelets[Up][7] = e(UF, false);
@@ -184,7 +285,7 @@ impl Cube333 {
         facelets[Down][1] = e(DF, false);
         facelets[Down][2] = c(DFR, 0);
         facelets[Down][3] = e(DL, false);
-        facelets[Down][4] = Yellow;
+        facelets[Down][4] = center(Down);
         facelets[Down][5] = e(DR, false);
         facelets[Down][6] = c(DBL, 0);
         facelets[Down][7] = e(DB, false);
@@ -194,7 +295,7 @@ impl Cube333 {
         facelets[Front][1] = e(UF, true);
         facelets[Front][2] = c(UFR, 2);
         facelets[Front][3] = e(FL, false);
-        facelets[Front][4] = Green;
+        facelets[Front][4] = center(Front);
         facelets[Front][5] = e(FR, false);
         facelets[Front][6] = c(DFL, 2);
         facelets[Front][7] = e(DF, true);
@@ -204,7 +305,7 @@ impl Cube333 {
         facelets[Back][1] = e(UB, true);
         facelets[Back][2] = c(UBL, 2);
         facelets[Back][3] = e(BR, false);
-        facelets[Back][4] = Blue;
+        facelets[Back][4] = center(Back);
         facelets[Back][5] = e(BL, false);
         facelets[Back][6] = c(DBR, 2);
         facelets[Back][7] = e(DB, true);
@@ -214,7 +315,7 @@ impl Cube333 {
         facelets[Left][1] = e(UL, true);
         facelets[Left][2] = c(UFL, 2);
         facelets[Left][3] = e(BL, true);
-        facelets[Left][4] = Orange;
+        facelets[Left][4] = center(Left);
         facelets[Left][5] = e(FL, true);
         facelets[Left][6] = c(DBL, 2);
         facelets[Left][7] = e(DL, true);
@@ -224,7 +325,7 @@ impl Cube333 {
         facelets[Right][1] = e(UR, true);
         facelets[Right][2] = c(UBR, 2);
         facelets[Right][3] = e(FR, true);
-        facelets[Right][4] = Red;
+        facelets[Right][4] = center(Right);
         facelets[Right][5] = e(BR, true);
         facelets[Right][6] = c(DFR, 2);
         facelets[Right][7] = e(DR, true);
@@ -233,3 +334,125 @@ impl Cube333 {
         facelets
     }
 }
+
+//The sticker of an edge that decides whether it is oriented on this axis. It's the one with a color of the other
+//axis that can't flip it (FB for UD, UD otherwise), and the one with a color of the axis itself if there is none
+fn eo_reference(colors: [CubeColor; 2], axis: CubeAxis) -> CubeColor {
+    let primary = if axis == CubeAxis::UD { CubeAxis::FB } else { CubeAxis::UD };
+    colors.iter()
+        .find(|c| color_axis(**c) == primary)
+        .or_else(|| colors.iter().find(|c| color_axis(**c) == axis))
+        .copied()
+        .unwrap()
+}
+
+//Whether the permutation needs an odd number of swaps
+fn parity(ids: &[u8]) -> bool {
+    let mut odd = false;
+    for i in 0..ids.len() {
+        for j in i + 1..ids.len() {
+            if ids[i] > ids[j] {
+                odd = !odd;
+            }
+        }
+    }
+    odd
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::algs::Algorithm;
+    use crate::puzzles::c333::render::{facelet_string, parse_facelet_string};
+    use crate::puzzles::puzzle::ApplyAlgorithm;
+
+    use super::*;
+
+    const SCRAMBLES: [&str; 6] = [
+        "",
+        "R' U' F",
+        "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+        "R' U' F D2 L2 F R2 U2 R2 B D2 L B2 D' B2 L' R' B D2 B U2 L U R' U' F",
+        "F2 L' D2 B2 U2 R B2 L U2 R' D' R2 F' R' U' B' L2 U' F'",
+        "D' L2 U R2 F2 D B2 U' L2 R B' U2 L' D F R2 U B' R",
+    ];
+
+    fn cube(alg: &str) -> Cube333 {
+        let mut cube = Cube333::default();
+        cube.apply_alg(&Algorithm::from_str(alg).unwrap());
+        cube
+    }
+
+    #[test]
+    fn facelets_round_trip() {
+        for scramble in SCRAMBLES {
+            let cube = cube(scramble);
+            assert_eq!(Cube333::from_facelets(&cube.get_facelets()), Ok(cube), "{scramble}");
+            let string = facelet_string(&cube.get_facelets());
+            assert_eq!(parse_facelet_string(string.as_str()).map(|f| facelet_string(&f)), Ok(string), "{scramble}");
+        }
+    }
+
+    #[test]
+    fn unreachable_facelets() {
+        let solved = Cube333::default().get_facelets();
+
+        let mut twisted = solved;
+        (twisted[Up][8], twisted[Front][2], twisted[Right][0]) = (solved[Front][2], solved[Right][0], solved[Up][8]);
+        assert_eq!(Cube333::from_facelets(&twisted), Err("A corner is twisted".to_string()));
+
+        let mut flipped = solved;
+        (flipped[Up][7], flipped[Front][1]) = (solved[Front][1], solved[Up][7]);
+        assert_eq!(Cube333::from_facelets(&flipped), Err("An edge is flipped".to_string()));
+
+        let mut swapped = solved;
+        (swapped[Up][7], swapped[Front][1], swapped[Up][5], swapped[Right][1]) = (solved[Up][5], solved[Right][1], solved[Up][7], solved[Front][1]);
+        assert_eq!(Cube333::from_facelets(&swapped), Err("Two pieces are swapped".to_string()));
+
+        let mut duplicate = solved;
+        (duplicate[Up][5], duplicate[Right][1]) = (solved[Up][7], solved[Front][1]);
+        assert!(Cube333::from_facelets(&duplicate).is_err());
+
+        let mut rotated = solved;
+        (rotated[Up][4], rotated[Front][4]) = (solved[Front][4], solved[Up][4]);
+        assert!(Cube333::from_facelets(&rotated).is_err());
+
+        assert!(parse_facelet_string("UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBB").is_err());
+        assert!(parse_facelet_string("UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBX").is_err());
+    }
+
+    #[test]
+    fn mirror_matches_mirrored_scramble() {
+        for scramble in SCRAMBLES {
+            for axis in [CubeAxis::UD, CubeAxis::FB, CubeAxis::LR] {
+                let mut mirrored = cube(scramble);
+                mirrored.mirror(axis);
+                let mut alg = Algorithm::from_str(scramble).unwrap();
+                alg.mirror(axis);
+                let mut expected = Cube333::default();
+                expected.apply_alg(&alg);
+                assert_eq!(mirrored, expected, "{scramble} mirrored on {axis:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        for scramble in SCRAMBLES {
+            let cube = cube(scramble);
+            assert_eq!(EdgeCube333::from_bytes(cube.edges.to_bytes()), Ok(cube.edges), "{scramble}");
+            assert_eq!(CornerCube333::from_bytes(cube.corners.to_bytes()), Ok(cube.corners), "{scramble}");
+        }
+        assert!(EdgeCube333::from_bytes([0xFF; 16]).is_err());
+        assert!(CornerCube333::from_bytes([0xFF; 16]).is_err());
+
+        let mut duplicate = EdgeCube333::default().to_bytes();
+        duplicate[1] = duplicate[0];
+        assert!(EdgeCube333::from_bytes(duplicate).is_err());
+
+        let mut unused = CornerCube333::default().to_bytes();
+        unused[8] = 1;
+        assert!(CornerCube333::from_bytes(unused).is_err());
+    }
+}
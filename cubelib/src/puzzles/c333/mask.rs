@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::puzzles::c333::Cube333;
+use crate::puzzles::c333::render::Facelets;
+use crate::puzzles::cube::{CubeAxis, CubeColor};
+
+pub const MASK_NAMES: [&str; 11] = ["eo-ud", "eo-fb", "eo-lr", "dr-ud", "dr-fb", "dr-lr", "htr", "cross", "corners", "edges", "none"];
+
+//The stickers that stay visible when rendering a cube. Masks move with the pieces,
+//so they are defined on the solved cube and hide the same stickers no matter where they end up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mask {
+    //Indexed by the ids returned by Cube333::get_sticker_ids
+    visible: [bool; 54],
+}
+
+impl Mask {
+    pub fn new(visible: [bool; 54]) -> Mask {
+        Mask { visible }
+    }
+
+    pub fn all() -> Mask {
+        Mask::new([true; 54])
+    }
+
+    //The facelets of the cube with all hidden stickers set to CubeColor::None
+    pub fn apply(&self, cube: &Cube333) -> Facelets {
+        let mut facelets = cube.get_facelets();
+        let ids = cube.get_sticker_ids();
+        for face in 0..6 {
+            for i in 0..9 {
+                if !self.visible[ids[face][i] as usize] {
+                    facelets[face][i] = CubeColor::None;
+                }
+            }
+        }
+        facelets
+    }
+
+    pub fn is_visible(&self, sticker_id: u8) -> bool {
+        self.visible[sticker_id as usize]
+    }
+
+    //Masks by a predicate on the color of a sticker and all colors of its piece. Centers are always visible
+    fn from_stickers<F: Fn(CubeColor, &[CubeColor]) -> bool>(f: F) -> Mask {
+        let solved = Cube333::default();
+        let ids = solved.get_sticker_ids();
+        let colors = solved.get_facelets();
+        let mut pieces: HashMap<u8, Vec<CubeColor>> = HashMap::new();
+        for face in 0..6 {
+            for i in 0..9 {
+                pieces.entry(piece(ids[face][i])).or_default().push(colors[face][i]);
+            }
+        }
+        let mut visible = [false; 54];
+        for face in 0..6 {
+            for i in 0..9 {
+                let id = ids[face][i];
+                visible[id as usize] = id >= 48 || f(colors[face][i], pieces[&piece(id)].as_slice());
+            }
+        }
+        Mask::new(visible)
+    }
+
+    //Shows the one sticker of every edge that decides its orientation on this axis
+    fn eo(axis: CubeAxis) -> Mask {
+        let primary = if axis == CubeAxis::UD { CubeAxis::FB } else { CubeAxis::UD };
+        Mask::from_stickers(|color, piece| {
+            if piece.len() != 2 {
+                false
+            } else if piece.iter().any(|c| color_axis(*c) == primary) {
+                color_axis(color) == primary
+            } else {
+                color_axis(color) == axis
+            }
+        })
+    }
+
+    fn dr(axis: CubeAxis) -> Mask {
+        Mask::from_stickers(|color, _| color_axis(color) == axis)
+    }
+}
+
+//Either one of the names in MASK_NAMES, or a custom mask with 54 characters in the same order as a facelet string (URFDLB, see render::facelet_string).
+//'1' or 'x' show the sticker that is at this position on a solved cube, '0' or '-' hide it. Whitespace is ignored.
+impl FromStr for Mask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mask = match s.trim() {
+            "eo-ud" => Mask::eo(CubeAxis::UD),
+            "eo-fb" => Mask::eo(CubeAxis::FB),
+            "eo-lr" => Mask::eo(CubeAxis::LR),
+            "dr-ud" => Mask::dr(CubeAxis::UD),
+            "dr-fb" => Mask::dr(CubeAxis::FB),
+            "dr-lr" => Mask::dr(CubeAxis::LR),
+            "htr" => Mask::from_stickers(|color, _| color_axis(color) != CubeAxis::LR),
+            "cross" => Mask::from_stickers(|_, piece| piece.len() == 2 && piece.iter().any(|c| matches!(c, CubeColor::Yellow))),
+            "corners" => Mask::from_stickers(|_, piece| piece.len() == 3),
+            "edges" => Mask::from_stickers(|_, piece| piece.len() == 2),
+            "none" => Mask::all(),
+            custom => return parse_custom(custom),
+        };
+        Ok(mask)
+    }
+}
+
+fn parse_custom(s: &str) -> Result<Mask, String> {
+    let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.len() != 54 {
+        return Err(format!("Unknown mask '{s}'. Expected one of {} or 54 characters of '1' and '0'", MASK_NAMES.join(", ")));
+    }
+    let ids = Cube333::default().get_sticker_ids();
+    let positions = super::render::FACELET_STRING_ORDER.iter()
+        .flat_map(|face| ids[*face].iter());
+    let mut visible = [false; 54];
+    for (c, id) in chars.iter().zip(positions) {
+        visible[*id as usize] = match c {
+            '1' | 'x' => true,
+            '0' | '-' => false,
+            c => return Err(format!("Invalid character '{c}' in mask. Expected '1' or '0'")),
+        };
+    }
+    Ok(Mask::new(visible))
+}
+
+//Stickers of the same piece map to the same key
+fn piece(sticker_id: u8) -> u8 {
+    match sticker_id {
+        0..=23 => sticker_id / 3,
+        24..=47 => 8 + (sticker_id - 24) / 2,
+        _ => sticker_id,
+    }
+}
+
+pub(crate) fn color_axis(color: CubeColor) -> CubeAxis {
+    match color {
+        CubeColor::White | CubeColor::Yellow => CubeAxis::UD,
+        CubeColor::Green | CubeColor::Blue => CubeAxis::FB,
+        //Hidden stickers have no axis. Callers check for them first
+        CubeColor::Orange | CubeColor::Red | CubeColor::None => CubeAxis::LR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algs::Algorithm;
+    use crate::puzzles::c333::render::facelet_string;
+    use crate::puzzles::puzzle::ApplyAlgorithm;
+
+    //The solved cube with the mask applied, as a facelet string in URFDLB order
+    fn solved(mask: &str) -> String {
+        let mask = Mask::from_str(mask).unwrap();
+        facelet_string(&mask.apply(&Cube333::default()))
+    }
+
+    #[test]
+    fn eo_masks_show_the_deciding_sticker_of_every_edge() {
+        assert_eq!(solved("eo-ud"), "---UUU---".to_string() + "----R----" + "-F-FFF-F-" + "---DDD---" + "----L----" + "-B-BBB-B-");
+        assert_eq!(solved("eo-fb"), "-U-UUU-U-".to_string() + "----R----" + "---FFF---" + "-D-DDD-D-" + "----L----" + "---BBB---");
+        assert_eq!(solved("eo-lr"), "-U-UUU-U-".to_string() + "---RRR---" + "----F----" + "-D-DDD-D-" + "---LLL---" + "----B----");
+    }
+
+    #[test]
+    fn dr_masks_show_the_stickers_of_the_axis() {
+        assert_eq!(solved("dr-ud"), "UUUUUUUUU".to_string() + "----R----" + "----F----" + "DDDDDDDDD" + "----L----" + "----B----");
+        assert_eq!(solved("dr-fb"), "----U----".to_string() + "----R----" + "FFFFFFFFF" + "----D----" + "----L----" + "BBBBBBBBB");
+        assert_eq!(solved("dr-lr"), "----U----".to_string() + "RRRRRRRRR" + "----F----" + "----D----" + "LLLLLLLLL" + "----B----");
+    }
+
+    #[test]
+    fn cross_mask_shows_the_d_layer_edges() {
+        assert_eq!(solved("cross"), "----U----".to_string() + "----R--R-" + "----F--F-" + "-D-DDD-D-" + "----L--L-" + "----B--B-");
+    }
+
+    #[test]
+    fn masks_move_with_the_pieces() {
+        let mask = Mask::from_str("dr-ud").unwrap();
+        let mut cube = Cube333::default();
+        cube.apply_alg(&Algorithm::from_str("R").unwrap());
+        assert_eq!(facelet_string(&mask.apply(&cube)), "UU-UU-UU-".to_string() + "----R----" + "--D-FD--D" + "DD-DD-DD-" + "----L----" + "U--UB-U--");
+    }
+
+    #[test]
+    fn custom_masks() {
+        let custom = format!("{} {}", "1".repeat(27), "x".repeat(27));
+        assert_eq!(Mask::from_str(custom.as_str()), Ok(Mask::all()));
+        let hidden = format!("{}{}", "0".repeat(53), "-");
+        assert_eq!(facelet_string(&Mask::from_str(hidden.as_str()).unwrap().apply(&Cube333::default())), "-".repeat(54));
+    }
+
+    #[test]
+    fn custom_masks_need_54_characters() {
+        let error = Mask::from_str("1".repeat(53).as_str()).unwrap_err();
+        assert_eq!(error, format!("Unknown mask '{}'. Expected one of {} or 54 characters of '1' and '0'", "1".repeat(53), MASK_NAMES.join(", ")));
+        assert!(Mask::from_str("1".repeat(55).as_str()).is_err());
+    }
+
+    #[test]
+    fn custom_masks_reject_invalid_characters() {
+        let mask = format!("{}a{}", "1".repeat(20), "0".repeat(33));
+        assert_eq!(Mask::from_str(mask.as_str()), Err("Invalid character 'a' in mask. Expected '1' or '0'".to_string()));
+    }
+}
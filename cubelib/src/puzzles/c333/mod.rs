@@ -1,6 +1,8 @@
 #[cfg(feature = "solver")]
 pub mod steps;
 mod cube;
+pub mod mask;
+pub mod render;
 pub mod util;
 
 pub type Transformation333 = crate::puzzles::cube::CubeTransformation;
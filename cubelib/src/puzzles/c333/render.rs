@@ -0,0 +1,200 @@
+use std::fmt::Write;
+
+use crate::puzzles::cube::{CubeColor, CubeFace};
+use crate::puzzles::cube::CubeFace::*;
+
+//The stickers of every face in the order returned by Cube333::get_facelets.
+//Each face is read row by row, the same way it is read in the facelet string
+pub type Facelets = [[CubeColor; 9]; 6];
+
+//Order of the faces in facelet strings, same as the Kociemba solver
+pub(crate) const FACELET_STRING_ORDER: [CubeFace; 6] = [Up, Right, Front, Down, Left, Back];
+
+const SVG_STICKER_SIZE: usize = 20;
+const SVG_BORDER: &str = "#202020";
+
+pub fn rgb(color: CubeColor) -> (u8, u8, u8) {
+    match color {
+        CubeColor::White => (255, 255, 255),
+        CubeColor::Yellow => (255, 213, 0),
+        CubeColor::Green => (0, 155, 72),
+        CubeColor::Blue => (0, 70, 173),
+        CubeColor::Orange => (255, 88, 0),
+        CubeColor::Red => (183, 18, 52),
+        CubeColor::None => (128, 128, 128),
+    }
+}
+
+//The face that has this color in the solved state. Stickers without a color are printed as '-'
+pub fn letter(color: CubeColor) -> char {
+    match color {
+        CubeColor::White => 'U',
+        CubeColor::Yellow => 'D',
+        CubeColor::Green => 'F',
+        CubeColor::Blue => 'B',
+        CubeColor::Orange => 'L',
+        CubeColor::Red => 'R',
+        CubeColor::None => '-',
+    }
+}
+
+//All 54 stickers as letters in URFDLB order, e.g. UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB for a solved cube
+pub fn facelet_string(facelets: &Facelets) -> String {
+    FACELET_STRING_ORDER.iter()
+        .flat_map(|face| facelets[*face].iter())
+        .map(|c| letter(*c))
+        .collect()
+}
+
+//The inverse of facelet_string. Whitespace is ignored, so nets copied from letter_net work as long as they are in URFDLB order
+pub fn parse_facelet_string(s: &str) -> Result<Facelets, String> {
+    let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.len() != 54 {
+        return Err(format!("Expected 54 facelets, got {}", chars.len()));
+    }
+    let mut facelets = [[CubeColor::None; 9]; 6];
+    for (n, c) in chars.iter().enumerate() {
+        facelets[FACELET_STRING_ORDER[n / 9]][n % 9] = match c.to_ascii_uppercase() {
+            'U' => CubeColor::White,
+            'D' => CubeColor::Yellow,
+            'F' => CubeColor::Green,
+            'B' => CubeColor::Blue,
+            'L' => CubeColor::Orange,
+            'R' => CubeColor::Red,
+            c => return Err(format!("Invalid facelet '{c}' at position {n}. Expected one of U, R, F, D, L, B")),
+        };
+    }
+    Ok(facelets)
+}
+
+pub fn letter_net(facelets: &Facelets) -> String {
+    net_rows(facelets).iter()
+        .map(|row| row.iter()
+            .map(|c| c.map_or(' ', letter))
+            .collect::<String>()
+            .trim_end()
+            .to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+//Two spaces with a 24 bit background color per sticker. Needs a terminal with truecolor support
+pub fn ansi_net(facelets: &Facelets) -> String {
+    net_rows(facelets).iter()
+        .map(|row| {
+            let mut line = String::new();
+            let last = row.iter().rposition(|c| c.is_some()).map_or(0, |i| i + 1);
+            for sticker in &row[0..last] {
+                match sticker {
+                    Some(color) => {
+                        let (r, g, b) = rgb(*color);
+                        write!(line, "\x1b[48;2;{r};{g};{b}m  \x1b[0m").unwrap();
+                    },
+                    None => line.push_str("  "),
+                }
+            }
+            line
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn svg_net(facelets: &Facelets) -> String {
+    let size = SVG_STICKER_SIZE;
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"-1 -1 {} {}\">", size * 12 + 2, size * 9 + 2, size * 12 + 2, size * 9 + 2);
+    for (y, row) in net_rows(facelets).iter().enumerate() {
+        for (x, sticker) in row.iter().enumerate() {
+            if let Some(color) = sticker {
+                write!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{}\" stroke=\"{SVG_BORDER}\"/>", x * size, y * size, hex(*color)).unwrap();
+            }
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+//Isometric view of the U, F and R faces
+pub fn svg_isometric(facelets: &Facelets) -> String {
+    let size = SVG_STICKER_SIZE as f64;
+    let (dx, dy) = (size * 30f64.to_radians().cos(), size * 30f64.to_radians().sin());
+    //x points to R, y to U and z to F. The origin is the corner shared by the U, B and L faces, projected to the center top
+    let project = |x: f64, y: f64, z: f64| ((x - z) * dx + 3.0 * dx + 1.0, (x + z) * dy + (3.0 - y) * size + 1.0);
+    let width = 6.0 * dx + 2.0;
+    let height = 6.0 * dy + 3.0 * size + 2.0;
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" viewBox=\"0 0 {width:.2} {height:.2}\">");
+    for (face, sticker) in [Up, Front, Right].iter().flat_map(|face| (0..9).map(move |i| (*face, i))) {
+        let (row, col) = ((sticker / 3) as f64, (sticker % 3) as f64);
+        let corners = match face {
+            Up => [(col, 3.0, row), (col + 1.0, 3.0, row), (col + 1.0, 3.0, row + 1.0), (col, 3.0, row + 1.0)],
+            Front => [(col, 3.0 - row, 3.0), (col + 1.0, 3.0 - row, 3.0), (col + 1.0, 2.0 - row, 3.0), (col, 2.0 - row, 3.0)],
+            _ => [(3.0, 3.0 - row, 3.0 - col), (3.0, 3.0 - row, 2.0 - col), (3.0, 2.0 - row, 2.0 - col), (3.0, 2.0 - row, 3.0 - col)],
+        };
+        let points = corners.iter()
+            .map(|(x, y, z)| {
+                let (px, py) = project(*x, *y, *z);
+                format!("{px:.2},{py:.2}")
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        write!(svg, "<polygon points=\"{points}\" fill=\"{}\" stroke=\"{SVG_BORDER}\" stroke-linejoin=\"round\"/>", hex(facelets[face][sticker])).unwrap();
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn hex(color: CubeColor) -> String {
+    let (r, g, b) = rgb(color);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+//The unfolded cube as 9 rows of 12 stickers, with U above and D below F:
+//   U
+// L F R B
+//   D
+fn net_rows(facelets: &Facelets) -> Vec<Vec<Option<CubeColor>>> {
+    let mut rows = vec![vec![None; 12]; 9];
+    for (face, row_offset, col_offset) in [(Up, 0, 3), (Left, 3, 0), (Front, 3, 3), (Right, 3, 6), (Back, 3, 9), (Down, 6, 3)] {
+        for i in 0..9 {
+            rows[row_offset + i / 3][col_offset + i % 3] = Some(facelets[face][i]);
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::puzzles::c333::Cube333;
+    use crate::puzzles::c333::mask::Mask;
+
+    #[test]
+    fn solved_facelet_string() {
+        assert_eq!(facelet_string(&Cube333::default().get_facelets()), "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB");
+    }
+
+    #[test]
+    fn letter_net_prints_hidden_stickers() {
+        let facelets = Mask::from_str("cross").unwrap().apply(&Cube333::default());
+        let expected = [
+            "   ---",
+            "   -U-",
+            "   ---",
+            "------------",
+            "-L--F--R--B-",
+            "-L--F--R--B-",
+            "   -D-",
+            "   DDD",
+            "   -D-",
+        ].join("\n");
+        assert_eq!(letter_net(&facelets), expected);
+    }
+
+    #[test]
+    fn parse_facelet_string_errors() {
+        assert_eq!(parse_facelet_string("U".repeat(53).as_str()), Err("Expected 54 facelets, got 53".to_string()));
+        let invalid = format!("{}X{}", "U".repeat(10), "U".repeat(43));
+        assert_eq!(parse_facelet_string(invalid.as_str()), Err("Invalid facelet 'X' at position 10. Expected one of U, R, F, D, L, B".to_string()));
+    }
+}
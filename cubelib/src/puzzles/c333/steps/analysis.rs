@@ -0,0 +1,299 @@
+use std::fmt::{Display, Formatter};
+
+use crate::co::COCount;
+use crate::defs::StepKind;
+use crate::puzzles::c333::Cube333;
+use crate::puzzles::c333::steps::Step333;
+use crate::puzzles::c333::steps::eo::eo_config::{self, EOCount};
+use crate::puzzles::c333::steps::tables::PruningTables333;
+use crate::puzzles::cube::CubeAxis;
+#[cfg(feature = "333dr")]
+use crate::puzzles::c333::steps::dr::{coords::DRUDEOFBCoord, dr_config};
+#[cfg(feature = "333htr")]
+use crate::puzzles::c333::steps::htr::{coords::HTRDRUDCoord, htr_config};
+#[cfg(feature = "333htr")]
+use crate::puzzles::c333::util::HTR_SUBSETS;
+#[cfg(feature = "333fr")]
+use crate::puzzles::c333::steps::fr::{coords::FRUDWithSliceCoord, fr_config};
+#[cfg(feature = "333finish")]
+use crate::puzzles::c333::steps::finish::finish_config;
+#[cfg(any(feature = "333dr", feature = "333fr"))]
+use crate::puzzles::c333::Transformation333;
+#[cfg(any(feature = "333dr", feature = "333fr"))]
+use crate::puzzles::puzzle::TransformableMut;
+#[cfg(any(feature = "333dr", feature = "333fr"))]
+use crate::steps::coord::Coord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde_support", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisValues<T> {
+    pub ud: T,
+    pub fb: T,
+    pub lr: T,
+}
+
+impl <T> AxisValues<T> {
+    fn from_fn<F: FnMut(CubeAxis) -> T>(mut f: F) -> Self {
+        AxisValues {
+            ud: f(CubeAxis::UD),
+            fb: f(CubeAxis::FB),
+            lr: f(CubeAxis::LR),
+        }
+    }
+
+    pub fn get(&self, axis: CubeAxis) -> &T {
+        match axis {
+            CubeAxis::UD => &self.ud,
+            CubeAxis::FB => &self.fb,
+            CubeAxis::LR => &self.lr,
+        }
+    }
+}
+
+impl <T> From<(T, T, T)> for AxisValues<T> {
+    fn from((ud, fb, lr): (T, T, T)) -> Self {
+        AxisValues { ud, fb, lr }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepDistance {
+    pub kind: StepKind,
+    pub variant: String,
+    pub distance: u8,
+}
+
+//Everything we know about a cube state without searching. Steps whose features or tables aren't available are left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(serde::Serialize, serde::Deserialize))]
+pub struct Analysis333 {
+    pub bad_edges: AxisValues<u8>,
+    pub bad_corners: AxisValues<u8>,
+    pub eo: AxisValues<bool>,
+    pub dr: AxisValues<bool>,
+    pub htr: bool,
+    pub fr: AxisValues<bool>,
+    //The HTR subset for every axis the cube is in DR on
+    pub htr_subset: AxisValues<Option<String>>,
+    //Optimal number of moves to the end of each step that can be started from this cube
+    pub distances: Vec<StepDistance>,
+}
+
+pub fn analyze(cube: &Cube333, tables: &PruningTables333) -> Analysis333 {
+    let bad_edges: AxisValues<u8> = cube.count_bad_edges().into();
+    let bad_corners: AxisValues<u8> = cube.co_count_all().into();
+    let eo = AxisValues::from_fn(|axis| *bad_edges.get(axis) == 0);
+    let dr = AxisValues::from_fn(|axis| is_dr(cube, axis));
+    let htr = dr.ud && is_htr(cube);
+    let fr = AxisValues::from_fn(|axis| htr && is_fr(cube, axis));
+    let htr_subset = AxisValues::from_fn(|axis| if *dr.get(axis) {
+        htr_subset(cube, axis, tables)
+    } else {
+        None
+    });
+    Analysis333 {
+        bad_edges,
+        bad_corners,
+        eo,
+        dr,
+        htr,
+        fr,
+        htr_subset,
+        distances: distances(cube, tables, htr, &fr),
+    }
+}
+
+//The most specific subgroup a cube is in. Axis values mark the axes the cube is in that subgroup on
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(serde::Serialize, serde::Deserialize))]
+pub enum Subgroup {
+    None,
+    EO(AxisValues<bool>),
+    DR(AxisValues<bool>),
+    HTR,
+    FR(AxisValues<bool>),
+    Solved,
+}
+
+impl Display for Subgroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let axes = |values: &AxisValues<bool>| [("ud", values.ud), ("fb", values.fb), ("lr", values.lr)].iter()
+            .filter(|(_, v)| *v)
+            .map(|(name, _)| *name)
+            .collect::<Vec<&str>>()
+            .join(", ");
+        match self {
+            Subgroup::None => write!(f, "none"),
+            Subgroup::EO(values) => write!(f, "EO {}", axes(values)),
+            Subgroup::DR(values) => write!(f, "DR {}", axes(values)),
+            Subgroup::HTR => write!(f, "HTR"),
+            Subgroup::FR(values) => write!(f, "FR {}", axes(values)),
+            Subgroup::Solved => write!(f, "solved"),
+        }
+    }
+}
+
+pub fn subgroup(cube: &Cube333) -> Subgroup {
+    if *cube == Cube333::default() {
+        return Subgroup::Solved;
+    }
+    let bad_edges: AxisValues<u8> = cube.count_bad_edges().into();
+    let eo = AxisValues::from_fn(|axis| *bad_edges.get(axis) == 0);
+    let dr = AxisValues::from_fn(|axis| is_dr(cube, axis));
+    let htr = dr.ud && is_htr(cube);
+    let fr = AxisValues::from_fn(|axis| htr && is_fr(cube, axis));
+    if fr.ud || fr.fb || fr.lr {
+        Subgroup::FR(fr)
+    } else if htr {
+        Subgroup::HTR
+    } else if dr.ud || dr.fb || dr.lr {
+        Subgroup::DR(dr)
+    } else if eo.ud || eo.fb || eo.lr {
+        Subgroup::EO(eo)
+    } else {
+        Subgroup::None
+    }
+}
+
+//Same rotations the HTR and FR steps use to move an axis to UD
+#[cfg(any(feature = "333dr", feature = "333fr"))]
+fn to_ud(cube: &Cube333, axis: CubeAxis) -> Cube333 {
+    let mut cube = *cube;
+    match axis {
+        CubeAxis::UD => {},
+        CubeAxis::FB => cube.transform(Transformation333::X),
+        CubeAxis::LR => cube.transform(Transformation333::Z),
+    }
+    cube
+}
+
+//The DR coordinate assumes that EO is solved, so the edge orientation on the two other axes is checked separately
+#[cfg(feature = "333dr")]
+fn is_dr(cube: &Cube333, axis: CubeAxis) -> bool {
+    let cube = to_ud(cube, axis);
+    let (_, bad_fb, bad_lr) = cube.count_bad_edges();
+    bad_fb == 0 && bad_lr == 0 && DRUDEOFBCoord::from(&cube).val() == 0
+}
+
+#[cfg(not(feature = "333dr"))]
+fn is_dr(_: &Cube333, _: CubeAxis) -> bool {
+    false
+}
+
+#[cfg(feature = "333htr")]
+fn is_htr(cube: &Cube333) -> bool {
+    HTRDRUDCoord::from(cube).val() == 0
+}
+
+#[cfg(not(feature = "333htr"))]
+fn is_htr(_: &Cube333) -> bool {
+    false
+}
+
+#[cfg(feature = "333fr")]
+fn is_fr(cube: &Cube333, axis: CubeAxis) -> bool {
+    FRUDWithSliceCoord::from(&to_ud(cube, axis)).val() == 0
+}
+
+#[cfg(not(feature = "333fr"))]
+fn is_fr(_: &Cube333, _: CubeAxis) -> bool {
+    false
+}
+
+#[cfg(feature = "333htr")]
+fn htr_subset(cube: &Cube333, axis: CubeAxis, tables: &PruningTables333) -> Option<String> {
+    let id = tables.htr_subset()?.get(HTRDRUDCoord::from(&to_ud(cube, axis)));
+    HTR_SUBSETS.get(id as usize).map(|subset| subset.to_string())
+}
+
+#[cfg(not(feature = "333htr"))]
+fn htr_subset(_: &Cube333, _: CubeAxis, _: &PruningTables333) -> Option<String> {
+    None
+}
+
+//The coordinates of the FR and finish steps only mean something if the cube is in HTR or FR, so those are checked here instead of by the steps
+#[cfg_attr(not(feature = "333fr"), allow(unused_variables))]
+fn distances(cube: &Cube333, tables: &PruningTables333, htr: bool, fr: &AxisValues<bool>) -> Vec<StepDistance> {
+    let mut distances = vec![];
+    let mut add = |step: Step333, variant: Option<&str>| {
+        for (name, distance) in step.distances(cube) {
+            distances.push(StepDistance {
+                kind: step.kind(),
+                variant: variant.map(|v| v.to_string()).unwrap_or(name),
+                distance,
+            });
+        }
+    };
+    if let Some(table) = tables.eo() {
+        add(eo_config::eo_any(table), None);
+    }
+    #[cfg(feature = "333dr")]
+    if let Some(table) = tables.dr() {
+        add(dr_config::dr_any(table), None);
+    }
+    #[cfg(feature = "333htr")]
+    if let Some(table) = tables.htr() {
+        add(htr_config::htr_any(table), None);
+    }
+    #[cfg(feature = "333fr")]
+    if let Some(table) = tables.fr().filter(|_| htr) {
+        add(fr_config::fr_any(table), None);
+    }
+    #[cfg(feature = "333fr")]
+    if let Some(table) = tables.fr_leave_slice().filter(|_| htr) {
+        add(fr_config::fr_no_slice_any(table), None);
+    }
+    #[cfg(feature = "333finish")]
+    if let Some(table) = tables.htr_finish().filter(|_| htr) {
+        add(finish_config::htr_finish(table), Some("htr"));
+    }
+    //The finish variants have no names, so every axis gets its own step
+    #[cfg(feature = "333finish")]
+    if let Some(table) = tables.fr_finish() {
+        for (axis, name) in [(CubeAxis::UD, "fr-ud"), (CubeAxis::FB, "fr-fb"), (CubeAxis::LR, "fr-lr")] {
+            if !*fr.get(axis) {
+                continue;
+            }
+            add(finish_config::fr_finish(table, vec![axis]), Some(name));
+        }
+    }
+    distances
+}
+
+#[cfg(all(test, feature = "333fr"))]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::algs::Algorithm;
+    use crate::puzzles::puzzle::ApplyAlgorithm;
+
+    use super::*;
+
+    fn cube(alg: &str) -> Cube333 {
+        let mut cube = Cube333::default();
+        cube.apply_alg(&Algorithm::from_str(alg).unwrap());
+        cube
+    }
+
+    //The superflip has solved corners and slices, which the DR and FR coordinates alone can't tell apart from a solved cube
+    #[test]
+    fn superflip_is_not_dr() {
+        let superflip = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+        for alg in [superflip.to_string(), format!("{superflip} D2")] {
+            let cube = cube(alg.as_str());
+            assert_eq!(subgroup(&cube), Subgroup::None, "{alg}");
+            for axis in [CubeAxis::UD, CubeAxis::FB, CubeAxis::LR] {
+                assert!(!is_dr(&cube, axis), "{alg} is in DR on {axis:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn subgroups() {
+        assert_eq!(subgroup(&cube("")), Subgroup::Solved);
+        assert_eq!(subgroup(&cube("F U")), Subgroup::EO(AxisValues { ud: false, fb: false, lr: true }));
+        assert_eq!(subgroup(&cube("R")), Subgroup::DR(AxisValues { ud: false, fb: false, lr: true }));
+        assert_eq!(subgroup(&cube("R2 U2 F2")), Subgroup::HTR);
+    }
+}
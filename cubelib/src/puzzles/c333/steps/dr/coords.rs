@@ -1,6 +1,7 @@
-use crate::puzzles::c333::{Cube333, EdgeCube333};
+use crate::puzzles::c333::{Cube333, EdgeCube333, Transformation333};
 use crate::puzzles::cube::coords::COUDCoord;
-use crate::steps::coord::Coord;
+use crate::puzzles::cube::CubeAxis;
+use crate::steps::coord::{Coord, min_conjugate_mirrored, SymCoord};
 
 //Coordinate representing the position of edges that belong into the UD slice.
 //0 if they are in the slice, they don't have to be in the correct position
@@ -62,6 +63,23 @@ impl From<&Cube333> for DRUDEOFBCoord {
     }
 }
 
+//Rotations that keep both the UD and the FB axis in place, so DR-UD states with FB-EO stay in the same subgroup.
+//Together with the LR mirror these are all 8 symmetries that do. The move set isn't closed under y, so the 16 symmetries
+//that only keep the UD axis in place can't be used for this table.
+pub const DRUDEOFB_SYMMETRIES: [&[Transformation333]; 4] = [
+    &[],
+    &[Transformation333::Y2],
+    &[Transformation333::X2],
+    &[Transformation333::Z2],
+];
+
+impl SymCoord<DRUDEOFB_SIZE, Cube333> for DRUDEOFBCoord {
+    #[inline]
+    fn representative(puzzle: &Cube333) -> Self {
+        min_conjugate_mirrored(puzzle, &DRUDEOFB_SYMMETRIES, |cube: &mut Cube333| cube.mirror(CubeAxis::LR))
+    }
+}
+
 #[cfg(target_feature = "avx2")]
 mod avx2 {
     use std::arch::x86_64::{__m128i, _mm_add_epi8, _mm_and_si128, _mm_cmpeq_epi8, _mm_extract_epi16, _mm_hadd_epi32, _mm_or_si128, _mm_sad_epu8, _mm_set1_epi32, _mm_set1_epi8, _mm_setr_epi32, _mm_setr_epi8, _mm_shuffle_epi32, _mm_shuffle_epi8, _mm_slli_si128, _mm_srli_epi32, _mm_sub_epi8};
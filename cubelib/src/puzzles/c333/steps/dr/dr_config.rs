@@ -3,7 +3,10 @@ use std::vec;
 use itertools::Itertools;
 
 use crate::defs::*;
+#[cfg(not(feature = "sym_tables"))]
 use crate::solver::lookup_table::LookupTable;
+#[cfg(feature = "sym_tables")]
+use crate::solver::lookup_table::SymLookupTable;
 use crate::solver::moveset::TransitionTable333;
 use crate::puzzles::c333::{Cube333, Transformation333, Turn333};
 use crate::puzzles::c333::steps::{MoveSet333, Step333};
@@ -13,6 +16,7 @@ use crate::puzzles::cube::{CubeAxis, CubeFace};
 use crate::puzzles::cube::CubeFace::*;
 use crate::puzzles::cube::Direction::*;
 use crate::steps::step::{AnyPostStepCheck, DefaultPruningTableStep, DefaultStepOptions, Step, StepVariant};
+use crate::steps::params::{self, ParamSpec};
 use crate::steps::step::StepConfig;
 
 pub const HTR_DR_UD_STATE_CHANGE_MOVES: &[Turn333] = &[
@@ -63,10 +67,17 @@ pub const DR_UD_EO_FB_MOVESET: MoveSet333 = MoveSet333 {
     transitions: &dr_transitions(Left),
 };
 
+#[cfg(not(feature = "sym_tables"))]
 pub type DRPruningTable = LookupTable<{ DRUDEOFB_SIZE }, DRUDEOFBCoord>;
-pub type DRPruningTableStep<'a> = DefaultPruningTableStep<'a, {DRUDEOFB_SIZE}, DRUDEOFBCoord, 2048, EOCoordFB, Turn333, Transformation333, Cube333, TransitionTable333>;
+#[cfg(feature = "sym_tables")]
+pub type DRPruningTable = SymLookupTable<{ DRUDEOFB_SIZE }, DRUDEOFBCoord>;
+pub type DRPruningTableStep<'a> = DefaultPruningTableStep<'a, {DRUDEOFB_SIZE}, DRUDEOFBCoord, 2048, EOCoordFB, Turn333, Transformation333, Cube333, TransitionTable333, DRPruningTable>;
+
+//DR with triggers is handled by dr_trigger_config, but both accept the same parameters
+pub const PARAMS: &[ParamSpec] = crate::puzzles::c333::steps::dr::dr_trigger_config::PARAMS;
 
 pub fn from_step_config(table: &DRPruningTable, config: StepConfig) -> Result<(Step333, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     let step = if let Some(substeps) = config.substeps {
         let variants: Result<Vec<Vec<Box<dyn StepVariant<Turn333, Transformation333, Cube333, TransitionTable333>>>>, String> = substeps.into_iter().map(|step| match step.to_lowercase().as_str() {
             "ud" | "drud" => Ok(dr_step_variants(table, vec![CubeAxis::FB, CubeAxis::LR], vec![CubeAxis::UD])),
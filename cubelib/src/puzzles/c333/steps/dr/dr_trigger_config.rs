@@ -18,9 +18,11 @@ use crate::puzzles::c333::steps::eo::eo_config::EOCount;
 use crate::puzzles::cube::CubeAxis;
 use crate::puzzles::cube::Direction::{Clockwise, Half};
 use crate::puzzles::puzzle::{TransformableMut, TurnableMut};
+use crate::solver::lookup_table::TableLookup;
 use crate::solver::moveset::TransitionTable333;
 use crate::steps::coord::Coord;
 use crate::steps::step::{DefaultStepOptions, PostStepCheck, PreStepCheck, Step, StepVariant};
+use crate::steps::params::{self, ParamKind, ParamSpec};
 use crate::steps::step::StepConfig;
 
 pub const DR_UD_EO_FB_TRIGGER_MOVESET: MoveSet333 = MoveSet333 {
@@ -39,7 +41,22 @@ pub struct DRTriggerStepTable<'a> {
     name: &'a str,
 }
 
+pub const PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        key: "triggers",
+        description: "comma separated list of algorithms, e.g. R,RUR,RU'R",
+        kind: ParamKind::List(check_trigger),
+    },
+];
+
+fn check_trigger(trigger: &str) -> Result<(), String> {
+    Algorithm::<Turn333>::from_str(trigger.to_uppercase().as_str())
+        .map(|_| ())
+        .map_err(|_| format!("Unable to parse trigger '{trigger}'"))
+}
+
 pub fn from_step_config(table: &DRPruningTable, config: StepConfig) -> Result<(Step333, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     let triggers = config.params
         .get("triggers")
         .iter()
@@ -212,8 +229,7 @@ impl<'a> StepVariant<Turn333, Transformation333, Cube333, TransitionTable333> fo
     }
 
     fn heuristic(&self, cube: &Cube333, _: u8, can_niss: bool) -> u8 {
-        let coord = DRUDEOFBCoord::from(cube);
-        let heuristic = self.table.get(coord);
+        let heuristic = self.table.lookup(cube);
         if can_niss {
             min(1, heuristic)
         } else {
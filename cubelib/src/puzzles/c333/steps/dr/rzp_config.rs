@@ -10,6 +10,7 @@ use crate::puzzles::cube::CubeFace::*;
 use crate::puzzles::cube::Direction::*;
 use crate::solver::moveset::TransitionTable333;
 use crate::steps::step::{DefaultStepOptions, PostStepCheck, PreStepCheck, Step, StepVariant};
+use crate::steps::params::{self, ParamSpec};
 use crate::steps::step::StepConfig;
 
 const QT_MOVES: [Turn333; 12] = [
@@ -66,7 +67,10 @@ pub struct RZPStep<'a> {
     is_any: bool,
 }
 
+pub const PARAMS: &[ParamSpec] = &[];
+
 pub fn from_step_config<'a>(config: StepConfig) -> Result<(Step333<'a>, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     // let step = rzp_any();
     let step = rzp(vec![CubeAxis::X, CubeAxis::Y, CubeAxis::Z]);
     let search_opts = DefaultStepOptions::new(
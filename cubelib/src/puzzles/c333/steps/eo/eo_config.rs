@@ -11,6 +11,7 @@ use crate::puzzles::cube::{CubeAxis, CubeFace, CubeOuterTurn};
 use crate::puzzles::cube::CubeFace::*;
 use crate::puzzles::cube::Direction::*;
 use crate::steps::step::{DefaultStepOptions, PostStepCheck, PreStepCheck, Step, StepVariant};
+use crate::steps::params::{self, ParamSpec};
 use crate::steps::step::StepConfig;
 
 pub const UD_EO_STATE_CHANGE_MOVES: &[CubeOuterTurn] = &[
@@ -116,7 +117,10 @@ pub struct EOStepTable<'a> {
     name: &'a str,
 }
 
+pub const PARAMS: &[ParamSpec] = &[];
+
 pub fn from_step_config(table: &EOPruningTable, config: StepConfig) -> Result<(Step333, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     let step = if let Some(substeps) = config.substeps {
         let axis: Result<Vec<CubeAxis>, String> = substeps.into_iter().map(|step| match step.to_lowercase().as_str() {
             "eoud" | "ud" => Ok(CubeAxis::UD),
@@ -1,5 +1,7 @@
-use crate::steps::coord::Coord;
+use crate::steps::coord::{Coord, min_conjugate_mirrored, SymCoord};
 use crate::puzzles::c333::Cube333;
+use crate::puzzles::c333::steps::htr::coords::HTRDRUD_SYMMETRIES;
+use crate::puzzles::cube::CubeAxis;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct FRUDFinishCoord(pub(crate) u8);
@@ -55,6 +57,13 @@ impl From<&Cube333> for HTRFinishCoord {
     }
 }
 
+impl SymCoord<HTR_FINISH_SIZE, Cube333> for HTRFinishCoord {
+    #[inline]
+    fn representative(puzzle: &Cube333) -> Self {
+        min_conjugate_mirrored(puzzle, &HTRDRUD_SYMMETRIES, |cube: &mut Cube333| cube.mirror(CubeAxis::LR))
+    }
+}
+
 #[cfg(target_feature = "avx2")]
 mod avx2 {
     use std::arch::x86_64::{_mm_and_si128, _mm_cmpeq_epi8, _mm_cmplt_epi8, _mm_extract_epi16, _mm_hadd_epi16, _mm_hadd_epi32, _mm_movemask_epi8, _mm_mullo_epi16, _mm_or_si128, _mm_sad_epu8, _mm_set1_epi8, _mm_set_epi16, _mm_set_epi8, _mm_setr_epi8, _mm_shuffle_epi8, _mm_srli_epi32};
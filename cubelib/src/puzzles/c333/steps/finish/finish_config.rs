@@ -2,6 +2,8 @@ use itertools::Itertools;
 
 use crate::defs::*;
 use crate::solver::lookup_table::LookupTable;
+#[cfg(feature = "sym_tables")]
+use crate::solver::lookup_table::SymLookupTable;
 use crate::solver::moveset::TransitionTable333;
 use crate::puzzles::c333::{Cube333, Transformation333, Turn333};
 use crate::puzzles::c333::steps::{fr, MoveSet333, Step333};
@@ -12,6 +14,7 @@ use crate::puzzles::cube::{CubeAxis, CubeFace};
 use crate::puzzles::cube::CubeFace::*;
 use crate::puzzles::cube::Direction::*;
 use crate::steps::step::{AnyPostStepCheck, DefaultPruningTableStep, DefaultStepOptions, Step, StepVariant};
+use crate::steps::params::{self, ParamSpec};
 use crate::steps::step::StepConfig;
 
 pub const FRUD_FINISH_MOVESET: MoveSet333 = MoveSet333 {
@@ -35,11 +38,17 @@ pub const HTR_FINISH_MOVESET: MoveSet333 = MoveSet333 {
 pub type FRFinishPruningTable = LookupTable<{ FR_FINISH_SIZE }, FRUDFinishCoord>;
 pub type FRFinishPruningTableStep<'a> = DefaultPruningTableStep::<'a, { FR_FINISH_SIZE }, FRUDFinishCoord, {FRUD_WITH_SLICE_SIZE}, FRUDWithSliceCoord, Turn333, Transformation333, Cube333, TransitionTable333>;
 
+#[cfg(not(feature = "sym_tables"))]
 pub type HTRFinishPruningTable = LookupTable<{ HTR_FINISH_SIZE }, HTRFinishCoord>;
-pub type HTRFinishPruningTableStep<'a> = DefaultPruningTableStep::<'a, { HTR_FINISH_SIZE }, HTRFinishCoord, {PURE_HTRDRUD_SIZE}, PureHTRDRUDCoord, Turn333, Transformation333, Cube333, TransitionTable333>;
+#[cfg(feature = "sym_tables")]
+pub type HTRFinishPruningTable = SymLookupTable<{ HTR_FINISH_SIZE }, HTRFinishCoord>;
+pub type HTRFinishPruningTableStep<'a> = DefaultPruningTableStep::<'a, { HTR_FINISH_SIZE }, HTRFinishCoord, {PURE_HTRDRUD_SIZE}, PureHTRDRUDCoord, Turn333, Transformation333, Cube333, TransitionTable333, HTRFinishPruningTable>;
 
 
+pub const PARAMS: &[ParamSpec] = &[];
+
 pub fn from_step_config_fr(table: &FRFinishPruningTable, config: StepConfig) -> Result<(Step333, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     let step = if let Some(substeps) = config.substeps {
         let axis: Result<Vec<CubeAxis>, String> = substeps.into_iter().map(|step| match step.to_lowercase().as_str() {
             "finishud" | "finud" | "ud" => Ok(CubeAxis::UD),
@@ -67,6 +76,7 @@ pub fn from_step_config_fr(table: &FRFinishPruningTable, config: StepConfig) ->
 }
 
 pub fn from_step_config_fr_leave_slice(table: &FRFinishPruningTable, config: StepConfig) -> Result<(Step333, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     let step = if let Some(substeps) = config.substeps {
         let axis: Result<Vec<CubeAxis>, String> = substeps.into_iter().map(|step| match step.to_lowercase().as_str() {
             "finishud" | "finud" | "ud" => Ok(CubeAxis::UD),
@@ -94,6 +104,7 @@ pub fn from_step_config_fr_leave_slice(table: &FRFinishPruningTable, config: Ste
 }
 
 pub fn from_step_config_htr(table: &HTRFinishPruningTable, config: StepConfig) -> Result<(Step333, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     let search_opts = DefaultStepOptions::new(
         config.min.unwrap_or(0),
         config.max.unwrap_or(10),
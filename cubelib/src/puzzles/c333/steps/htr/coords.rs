@@ -1,6 +1,7 @@
-use crate::puzzles::c333::{CornerCube333, Cube333, EdgeCube333};
+use crate::puzzles::c333::{CornerCube333, Cube333, EdgeCube333, Transformation333};
 use crate::puzzles::cube::coords::CPCoord;
-use crate::steps::coord::Coord;
+use crate::puzzles::cube::CubeAxis;
+use crate::steps::coord::{Coord, min_conjugate_mirrored, SymCoord};
 
 //Coordinate representing the position of edges that belong into the FB slice, assuming the UD slice is already correct.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -183,6 +184,26 @@ impl From<&Cube333> for ImpureHTRDRUDCoord {
     }
 }
 
+//Rotations that keep the UD axis in place. Together with the LR mirror these are the 16 symmetries of a DR-UD state,
+//and the HTR and HTR finish move sets are closed under all of them
+pub const HTRDRUD_SYMMETRIES: [&[Transformation333]; 8] = [
+    &[],
+    &[Transformation333::Y],
+    &[Transformation333::Y2],
+    &[Transformation333::Yi],
+    &[Transformation333::X2],
+    &[Transformation333::X2, Transformation333::Y],
+    &[Transformation333::X2, Transformation333::Y2],
+    &[Transformation333::X2, Transformation333::Yi],
+];
+
+impl SymCoord<PURE_HTRDRUD_SIZE, Cube333> for PureHTRDRUDCoord {
+    #[inline]
+    fn representative(puzzle: &Cube333) -> Self {
+        min_conjugate_mirrored(puzzle, &HTRDRUD_SYMMETRIES, |cube: &mut Cube333| cube.mirror(CubeAxis::LR))
+    }
+}
+
 #[cfg(target_feature = "avx2")]
 mod avx2 {
     use std::arch::x86_64::{__m128i, _mm_add_epi8, _mm_and_si128, _mm_castps_si128, _mm_castsi128_ps, _mm_cmpeq_epi8, _mm_cmplt_epi8, _mm_extract_epi16, _mm_extract_epi64, _mm_hadd_epi32, _mm_movemask_epi8, _mm_or_si128, _mm_permute_ps, _mm_sad_epu8, _mm_set1_epi8, _mm_setr_epi32, _mm_setr_epi8, _mm_shuffle_epi32, _mm_shuffle_epi8, _mm_slli_epi16, _mm_slli_epi32, _mm_srli_epi32, _mm_srli_epi64, _mm_sub_epi8, _mm_xor_si128};
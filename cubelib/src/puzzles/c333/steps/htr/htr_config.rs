@@ -12,13 +12,21 @@ use crate::puzzles::c333::steps::htr::subsets::Subset;
 use crate::puzzles::c333::util::expand_subset_name;
 use crate::puzzles::cube::CubeAxis;
 use crate::puzzles::cube::Direction::*;
-use crate::solver::lookup_table::{LookupTable, NissLookupTable};
+use crate::solver::lookup_table::{LookupTable, NissTableLookup};
+#[cfg(not(feature = "sym_tables"))]
+use crate::solver::lookup_table::NissLookupTable;
+#[cfg(feature = "sym_tables")]
+use crate::solver::lookup_table::SymLookupTable;
 use crate::solver::moveset::TransitionTable333;
 use crate::steps::coord::Coord;
 use crate::steps::step::{DefaultStepOptions, PostStepCheck, PreStepCheck, Step, StepVariant};
+use crate::steps::params::{self, ParamKind, ParamSpec};
 use crate::steps::step::StepConfig;
 
+#[cfg(not(feature = "sym_tables"))]
 pub type HTRPruningTable = NissLookupTable<{ HTRDRUD_SIZE }, HTRDRUDCoord>;
+#[cfg(feature = "sym_tables")]
+pub type HTRPruningTable = SymLookupTable<{ HTRDRUD_SIZE }, HTRDRUDCoord>;
 pub type HTRSubsetTable = LookupTable<{ HTRDRUD_SIZE }, HTRDRUDCoord>;
 
 pub struct HTRPruningTableStep<'a, PSC: PreStepCheck<Turn333, Transformation333, Cube333>> {
@@ -109,8 +117,7 @@ impl<'a, PSC: PreStepCheck<Turn333, Transformation333, Cube333>> StepVariant<Tur
     }
 
     fn heuristic(&self, cube: &Cube333, _: u8, can_niss: bool) -> u8 {
-        let coord = HTRDRUDCoord::from(cube);
-        let (val, niss) = self.table.get(coord);
+        let (val, niss) = self.table.lookup_niss(cube);
         if can_niss && val != 0 {
             niss
         } else {
@@ -123,7 +130,28 @@ impl<'a, PSC: PreStepCheck<Turn333, Transformation333, Cube333>> StepVariant<Tur
     }
 }
 
+pub const PARAMS: &[ParamSpec] = &[
+    ParamSpec {
+        key: "subsets",
+        description: "comma separated list of subset names like 4a1 or numbers of corner quarter turns",
+        kind: ParamKind::List(check_subset),
+    },
+];
+
+fn check_subset(subset: &str) -> Result<(), String> {
+    if expand_subset_name(subset).is_empty() {
+        let names = crate::puzzles::c333::util::HTR_SUBSETS.iter()
+            .map(|s| s.to_string().split(' ').next().unwrap().to_string())
+            .unique()
+            .join(", ");
+        Err(format!("Unrecognized subset '{subset}'. Expected a number of corner quarter turns or one of {names}"))
+    } else {
+        Ok(())
+    }
+}
+
 pub fn from_step_config<'a>(table: &'a HTRPruningTable, subset_table: &'a HTRSubsetTable, config: StepConfig) -> Result<(Step333<'a>, DefaultStepOptions), String> {
+    params::validate(&config, PARAMS)?;
     let subsets = config.params.get("subsets")
         .map(|x|x.split(",").map(|x|x.to_string()).collect_vec());
 
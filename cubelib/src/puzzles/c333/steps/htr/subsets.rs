@@ -1,29 +1,40 @@
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::str::FromStr;
 use itertools::Itertools;
 use log::{debug, trace};
 use crate::algs::Algorithm;
 use crate::puzzles::c333::{Cube333, Turn333};
 use crate::puzzles::c333::steps::htr::coords::HTRDRUDCoord;
+#[cfg(not(feature = "sym_tables"))]
+use crate::puzzles::c333::steps::htr::coords::PureHTRDRUDCoord;
 use crate::puzzles::c333::steps::htr::htr_config::{HTR_DR_UD_MOVESET, HTRPruningTable, HTRSubsetTable};
 use crate::puzzles::cube::Direction;
 use crate::puzzles::puzzle::{ApplyAlgorithm, InvertibleMut, TurnableMut};
+use crate::solver::lookup_table::{EmptyVal, par_chunks};
 use crate::steps::coord::Coord;
 
 pub type Subset = crate::puzzles::c333::util::Subset;
 pub const HTR_SUBSETS: [Subset; 48] = crate::puzzles::c333::util::HTR_SUBSETS;
 
-pub fn gen_subset_tables(htr_table: &mut HTRPruningTable) -> HTRSubsetTable {
+//Also fills in the NISS values of the HTR table, see fill_niss_values
+pub fn gen_subset_tables(htr_table: &mut HTRPruningTable) -> Result<HTRSubsetTable, String> {
+    let subset_table = gen_subset_table();
+    fill_niss_values(htr_table, &subset_table)?;
+    Ok(subset_table)
+}
+
+pub fn gen_subset_table() -> HTRSubsetTable {
     let mut subset_table = HTRSubsetTable::new(false);
 
     let table_size = HTRDRUDCoord::size();
     let mut total_checked = 0;
+    let coset_0 = gen_coset_0();
 
     for (subset, id) in HTR_SUBSETS.iter().zip(1..) {
         debug!("Generating NISS table for subset: {id}. {subset:?}");
         let generator = Algorithm::from_str(subset.generator).unwrap();
-        let checked = fill_table(htr_table, &mut subset_table, &generator, id - 1);
+        let checked = fill_table(&mut subset_table, &coset_0, &generator, id - 1);
         total_checked += checked;
         debug!(
             "Checked {:width$}/{} cubes (new {})",
@@ -36,6 +47,60 @@ pub fn gen_subset_tables(htr_table: &mut HTRPruningTable) -> HTRSubsetTable {
     subset_table
 }
 
+//The NISS value of an HTR state is the number of moves that can't be skipped with NISS in the generator of its subset
+#[cfg(not(feature = "sym_tables"))]
+pub fn fill_niss_values(htr_table: &mut HTRPruningTable, subset_table: &HTRSubsetTable) -> Result<(), String> {
+    let niss_bounds = niss_bounds();
+    for id in 0..HTRDRUDCoord::size() {
+        let coord = PureHTRDRUDCoord(id as u16);
+        let subset = subset_table.get(coord);
+        if subset != subset_table.empty_val() {
+            htr_table.set_niss(coord, niss_bounds[subset as usize]);
+        }
+    }
+    Ok(())
+}
+
+//Conjugate states can belong to different subsets, so every class keeps the smallest NISS value of its states.
+//The subset table isn't reduced, so this visits every DR state once to find its class
+#[cfg(feature = "sym_tables")]
+pub fn fill_niss_values(htr_table: &mut HTRPruningTable, subset_table: &HTRSubsetTable) -> Result<(), String> {
+    use std::cmp::min;
+    use crate::steps::coord::SymCoord;
+
+    let niss_bounds = niss_bounds();
+    let start = Cube333::default();
+    let mut checked = HashSet::from([HTRDRUDCoord::from(&start)]);
+    let mut to_check = vec![start];
+    while !to_check.is_empty() {
+        let mut check_next = vec![];
+        for cube in to_check {
+            let subset = subset_table.get(HTRDRUDCoord::from(&cube));
+            if subset != subset_table.empty_val() {
+                let representative = HTRDRUDCoord::representative(&cube);
+                let (_, niss) = htr_table.get_niss(representative)
+                    .ok_or_else(|| format!("{} is not a representative", representative.val()))?;
+                htr_table.set_niss(representative, min(niss, niss_bounds[subset as usize]))?;
+            }
+            for m in HTR_DR_UD_MOVESET.aux_moves.iter().chain(HTR_DR_UD_MOVESET.st_moves.iter()) {
+                let mut next = cube;
+                next.turn(*m);
+                if checked.insert(HTRDRUDCoord::from(&next)) {
+                    check_next.push(next);
+                }
+            }
+        }
+        to_check = check_next;
+    }
+    Ok(())
+}
+
+pub(crate) fn niss_bounds() -> Vec<u8> {
+    HTR_SUBSETS.iter()
+        .map(|subset| min_niss_moves(&Algorithm::from_str(subset.generator).unwrap()))
+        .collect_vec()
+}
+
 fn min_niss_moves(alg: &Algorithm<Turn333>) -> u8 {
     let mut max_half_turns = 0;
     let mut current_half_turns = 0;
@@ -51,7 +116,8 @@ fn min_niss_moves(alg: &Algorithm<Turn333>) -> u8 {
 }
 
 fn gen_coset_0() -> Vec<Cube333> {
-    let mut checked = HashMap::new();
+    let mut checked = HashSet::new();
+    let mut coset = vec![];
     let mut to_check: Vec<Cube333> = vec![Cube333::default()];
     let mut check_next: Vec<Cube333> = vec![];
 
@@ -64,10 +130,10 @@ fn gen_coset_0() -> Vec<Cube333> {
                     cube
                 }) {
                 let coord = HTRDRUDCoord::from(&cube);
-                if checked.contains_key(&coord) {
+                if !checked.insert(coord) {
                     continue;
                 }
-                checked.insert(coord, cube);
+                coset.push(cube);
                 check_next.push(cube);
             }
         }
@@ -77,57 +143,62 @@ fn gen_coset_0() -> Vec<Cube333> {
         to_check = check_next;
         check_next = vec![];
     }
-    checked.values().cloned().collect_vec()
+    coset
 }
 
-fn fill_table(htr_table: &mut HTRPruningTable, subset_table: &mut HTRSubsetTable, generator: &Algorithm<Turn333>, subset_id: u8) -> usize {
+fn fill_table(subset_table: &mut HTRSubsetTable, coset_0: &[Cube333], generator: &Algorithm<Turn333>, subset_id: u8) -> usize {
     let mut total_checked = 0;
-    let niss_bound = min_niss_moves(generator);
-    let mut to_check: Vec<Cube333> = gen_coset_0()
-        .into_iter()
-        .flat_map(|c|{
-            vec![c].into_iter()
-                .flat_map(|mut a| {
-                    let b = a.clone();
-                    a.turn(Turn333::U);
-                    a.turn(Turn333::D);
-                    vec![a, b].into_iter()
-                })
-                .map(|mut c|{
-                    c.apply_alg(generator);
-                    c
-                })
+    let mut to_check: Vec<Cube333> = coset_0
+        .iter()
+        .cloned()
+        .flat_map(|mut a| {
+            let b = a.clone();
+            a.turn(Turn333::U);
+            a.turn(Turn333::D);
+            vec![a, b].into_iter()
+        })
+        .map(|mut c|{
+            c.apply_alg(generator);
+            c
         })
         .collect_vec();
-    let mut check_next: Vec<Cube333> = vec![];
     loop {
         trace!("To check: {}", to_check.len());
-        for cube in to_check.iter().cloned().flat_map(|mut a|{
-            let b = a.clone();
-            a.invert();
-            vec![a, b].into_iter()
-        }) {
-            for cube in HTR_DR_UD_MOVESET.aux_moves.iter().cloned()
-                .map(|m|{
-                    let mut cube = cube.clone();
-                    cube.turn(m);
-                    cube
-                }) {
-                let coord = HTRDRUDCoord::from(&cube);
-                let (_, niss) = htr_table.get(coord);
-                if niss != htr_table.empty_val() {
-                    continue;
+        let shared_table: &HTRSubsetTable = subset_table;
+        let candidates = par_chunks(&to_check, |chunk| {
+            let mut seen: HashSet<HTRDRUDCoord> = HashSet::new();
+            let mut found: Vec<(HTRDRUDCoord, Cube333)> = vec![];
+            for cube in chunk.iter().cloned().flat_map(|mut a|{
+                let b = a.clone();
+                a.invert();
+                vec![a, b].into_iter()
+            }) {
+                for cube in HTR_DR_UD_MOVESET.aux_moves.iter().cloned()
+                    .map(|m|{
+                        let mut cube = cube.clone();
+                        cube.turn(m);
+                        cube
+                    }) {
+                    let coord = HTRDRUDCoord::from(&cube);
+                    if shared_table.get(coord) == shared_table.empty_val() && seen.insert(coord) {
+                        found.push((coord, cube));
+                    }
                 }
-                htr_table.set_niss(coord, niss_bound);
-                subset_table.set(coord, subset_id);
-                check_next.push(cube);
             }
+            found
+        });
+        let mut check_next: Vec<Cube333> = vec![];
+        for (coord, cube) in candidates.into_iter().flat_map(|c| c.into_iter()) {
+            if subset_table.get(coord) != subset_table.empty_val() {
+                continue;
+            }
+            subset_table.set(coord, subset_id);
+            check_next.push(cube);
         }
         if check_next.is_empty() {
             break total_checked;
         }
         total_checked += check_next.len();
         to_check = check_next;
-        check_next = vec![];
     }
-}
\ No newline at end of file
+}
@@ -5,29 +5,36 @@ use crate::puzzles::c333::cube::Cube333;
 use crate::puzzles::c333::{Transformation333, Turn333};
 use crate::steps::step::{DefaultStepOptions, Step, StepConfig};
 use crate::puzzles::c333::steps;
+use crate::puzzles::c333::steps::tables;
 use crate::puzzles::c333::steps::tables::PruningTables333;
 
+//Makes sure all tables needed for these steps are loaded up front instead of during the first solve
 pub fn gen_tables(steps: &Vec<StepConfig>, tables: &mut PruningTables333) {
     let previous = vec![None].into_iter().chain(steps.iter().map(|x|Some(x.kind.clone()))).collect_vec();
     let steps = steps.into_iter().zip(previous.into_iter()).collect_vec();
 
     for (conf, pre) in steps.iter() {
-        match (pre.clone(), conf.kind.clone()) {
+        let keys: Vec<&str> = match (pre.clone(), conf.kind.clone()) {
             #[cfg(feature = "333eo")]
-            (_, StepKind::EO) => tables.gen_eo(),
+            (_, StepKind::EO) => vec![tables::EO_KEY],
             #[cfg(feature = "333dr")]
-            (_, StepKind::DR) => tables.gen_dr(),
+            (_, StepKind::DR) => vec![tables::DR_KEY],
             #[cfg(feature = "333htr")]
-            (_, StepKind::HTR) => tables.gen_htr(),
+            (_, StepKind::HTR) => vec![tables::HTR_KEY, tables::HTR_SUBSET_KEY],
             #[cfg(feature = "333fr")]
-            (_, StepKind::FR) => tables.gen_fr(),
+            (_, StepKind::FR) => vec![tables::FR_KEY],
             #[cfg(feature = "333fr")]
-            (_, StepKind::FRLS) => tables.gen_fr_leave_slice(),
+            (_, StepKind::FRLS) => vec![tables::FRLS_KEY],
             #[cfg(feature = "333finish")]
-            (Some(StepKind::FR), StepKind::FIN) | (Some(StepKind::FRLS), StepKind::FIN) => tables.gen_fr_finish(),
+            (Some(StepKind::FR), StepKind::FIN) | (Some(StepKind::FRLS), StepKind::FIN) => vec![tables::FR_FINISH_KEY],
             #[cfg(feature = "333finish")]
-            (Some(StepKind::HTR), StepKind::FIN) => tables.gen_htr_finish(),
-            _ => ()
+            (Some(StepKind::HTR), StepKind::FIN) => vec![tables::HTR_FINISH_KEY],
+            _ => vec![]
+        };
+        for key in keys {
+            if let Err(e) = tables.gen(key) {
+                log::error!("{e}");
+            }
         }
     }
 }
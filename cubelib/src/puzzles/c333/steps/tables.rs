@@ -1,9 +1,7 @@
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 
-use log::{debug, info};
-#[cfg(feature = "fs")]
-use log::error;
+use log::{debug, error, info};
 
 #[cfg(feature = "333dr")]
 use crate::puzzles::c333::steps::dr::coords::DRUDEOFBCoord;
@@ -26,29 +24,32 @@ use crate::puzzles::c333::steps::htr::coords::HTRDRUDCoord;
 #[cfg(feature = "333htr")]
 use crate::puzzles::c333::steps::htr::htr_config::{HTR_DR_UD_MOVESET, HTRPruningTable, HTRSubsetTable};
 use crate::solver::lookup_table;
-#[cfg(feature = "fs")]
-use crate::solver::lookup_table::{LoadFromDisk, SaveToDisk, LookupTable, NissLookupTable};
-#[cfg(feature = "fs")]
-use crate::steps::coord::Coord;
+use crate::solver::table_registry::TableRegistry;
+#[cfg(feature = "embedded_tables")]
+use crate::embedded;
+
+pub const EO_KEY: &str = "eo";
+//Symmetry reduced tables are stored under their own keys so they never get mixed up with the full tables
+#[cfg(not(feature = "sym_tables"))]
+pub const DR_KEY: &str = "dr";
+#[cfg(feature = "sym_tables")]
+pub const DR_KEY: &str = "dr-sym";
+#[cfg(not(feature = "sym_tables"))]
+pub const HTR_KEY: &str = "htr";
+#[cfg(feature = "sym_tables")]
+pub const HTR_KEY: &str = "htr-sym";
+pub const HTR_SUBSET_KEY: &str = "htr-subset";
+pub const FR_KEY: &str = "fr";
+pub const FRLS_KEY: &str = "frls";
+pub const FR_FINISH_KEY: &str = "frfin";
+#[cfg(not(feature = "sym_tables"))]
+pub const HTR_FINISH_KEY: &str = "htrfin";
+#[cfg(feature = "sym_tables")]
+pub const HTR_FINISH_KEY: &str = "htrfin-sym";
 
 #[derive(Clone)]
 pub struct PruningTables333 {
-    #[cfg(feature = "333eo")]
-    eo: Option<EOPruningTable>,
-    #[cfg(feature = "333dr")]
-    dr: Option<DRPruningTable>,
-    #[cfg(feature = "333htr")]
-    htr: Option<HTRPruningTable>,
-    #[cfg(feature = "333htr")]
-    htr_subset: Option<HTRSubsetTable>,
-    #[cfg(feature = "333fr")]
-    frls: Option<FRLeaveSlicePruningTable>,
-    #[cfg(feature = "333fr")]
-    fr: Option<FRPruningTable>,
-    #[cfg(feature = "333finish")]
-    fr_finish: Option<FRFinishPruningTable>,
-    #[cfg(feature = "333finish")]
-    htr_finish: Option<HTRFinishPruningTable>
+    registry: TableRegistry,
 }
 
 impl PruningTables333 {
@@ -56,303 +57,174 @@ impl PruningTables333 {
     pub const VERSION: u32 = 2;
 
     pub fn new() -> PruningTables333 {
+        let mut registry = TableRegistry::new("333");
+        #[cfg(feature = "333eo")]
+        registry.register(EO_KEY, gen_eo);
+        #[cfg(feature = "333dr")]
+        registry.register(DR_KEY, gen_dr);
+        //The NISS values of the HTR table are derived from the subset of every state
+        #[cfg(feature = "333htr")]
+        registry.register_derived(HTR_KEY, |registry: &TableRegistry| {
+            let mut htr_table = gen_htr();
+            let subset_table = registry.get::<HTRSubsetTable>(HTR_SUBSET_KEY)?;
+            crate::puzzles::c333::steps::htr::subsets::fill_niss_values(&mut htr_table, subset_table)?;
+            Ok(htr_table)
+        });
+        #[cfg(feature = "333htr")]
+        registry.register(HTR_SUBSET_KEY, gen_htr_subsets);
+        #[cfg(feature = "333fr")]
+        registry.register(FR_KEY, gen_fr);
+        #[cfg(feature = "333fr")]
+        registry.register(FRLS_KEY, gen_fr_leave_slice);
+        #[cfg(feature = "333finish")]
+        registry.register(FR_FINISH_KEY, gen_fr_finish);
+        #[cfg(feature = "333finish")]
+        registry.register(HTR_FINISH_KEY, gen_htr_finish);
+        #[cfg(feature = "embedded_tables")]
+        register_embedded_tables(&mut registry);
         PruningTables333 {
-            #[cfg(feature = "333eo")]
-            eo: None,
-            #[cfg(feature = "333dr")]
-            dr: None,
-            #[cfg(feature = "333htr")]
-            htr: None,
-            #[cfg(feature = "333htr")]
-            htr_subset: None,
-            #[cfg(feature = "333fr")]
-            frls: None,
-            #[cfg(feature = "333fr")]
-            fr: None,
-            #[cfg(feature = "333finish")]
-            fr_finish: None,
-            #[cfg(feature = "333finish")]
-            htr_finish: None
+            registry
         }
     }
 
-    #[cfg(feature = "fs")]
-    pub fn save(&self, key: &str) -> std::io::Result<()> {
-        match key {
-            #[cfg(feature = "333eo")]
-            "eo" => if let Some(tbl) = &self.eo {
-                tbl.save_to_disk("333", key)?
-            },
-            #[cfg(feature = "333dr")]
-            "dr" => if let Some(tbl) = &self.dr {
-                tbl.save_to_disk("333", key)?
-            },
-            #[cfg(feature = "333htr")]
-            "htr" => if let Some(tbl) = &self.htr {
-                tbl.save_to_disk("333", key)?;
-            },
-            #[cfg(feature = "333htr")]
-            "htr-subset" => if let Some(tbl) = &self.htr_subset {
-                tbl.save_to_disk("333", format!("{key}").as_str())?;
-            },
-            #[cfg(feature = "333fr")]
-            "fr" => if let Some(tbl) = &self.fr {
-                tbl.save_to_disk("333", key)?
-            },
-            #[cfg(feature = "333fr")]
-            "frls" => if let Some(tbl) = &self.frls {
-                tbl.save_to_disk("333", key)?
-            },
-            #[cfg(feature = "333finish")]
-            "frfin" => if let Some(tbl) = &self.fr_finish {
-                tbl.save_to_disk("333", key)?
-            },
-            #[cfg(feature = "333finish")]
-            "htrfin" => if let Some(tbl) = &self.htr_finish {
-                tbl.save_to_disk("333", key)?
-            },
-            _ => {}
-        }
-        Ok(())
+    //Custom steps can register their own tables here
+    pub fn registry(&self) -> &TableRegistry {
+        &self.registry
     }
 
-    #[cfg(feature = "fs")]
-    pub fn load(&mut self, key: &str) -> Result<(), String> {
-        match key {
-            #[cfg(feature = "333eo")]
-            "eo" => self.eo = Some(EOPruningTable::load_from_disk("333", key)?),
-            #[cfg(feature = "333dr")]
-            "dr" => self.dr = Some(DRPruningTable::load_from_disk("333", key)?),
-            #[cfg(feature = "333htr")]
-            "htr" => {
-                self.htr = Some(HTRPruningTable::load_from_disk("333", key)?);
-                self.htr_subset = Some(HTRSubsetTable::load_from_disk("333", format!("{key}-subset").as_str())?)
-            },
-            #[cfg(feature = "333fr")]
-            "fr" => self.fr = Some(FRPruningTable::load_from_disk("333", key)?),
-            #[cfg(feature = "333fr")]
-            "frls" => self.frls = Some(FRLeaveSlicePruningTable::load_from_disk("333", key)?),
-            #[cfg(feature = "333finish")]
-            "frfin" => self.fr_finish = Some(FRFinishPruningTable::load_from_disk("333", key)?),
-            #[cfg(feature = "333finish")]
-            "htrfin" => self.htr_finish = Some(HTRFinishPruningTable::load_from_disk("333", key)?),
-            _ => {}
-        }
-        Ok(())
+    pub fn registry_mut(&mut self) -> &mut TableRegistry {
+        &mut self.registry
     }
 
-    #[cfg(feature = "fs")]
-    pub fn load_and_gen_normal<const C_SIZE: usize, C: Coord<C_SIZE>>(key: &str, val: &mut Option<LookupTable<C_SIZE, C>>, gen_f: &dyn Fn() -> LookupTable<C_SIZE, C>, load_f: &dyn Fn() -> Result<LookupTable<C_SIZE, C>, String>) -> bool {
-        if val.is_none() {
-            let res = load_f();
-            match res {
-                Ok(v) => {
-                    *val = Some(v);
-                    info!("Loaded {key} table from disk");
-                },
-                Err(e) => {
-                    error!("Error loading {key} table from disk: {e}");
-                }
-            }
-        }
-        if val.is_none() {
-            let table = gen_f();
-            *val = Some(table);
-            return true;
-        }
-        false
+    pub fn keys(&self) -> Vec<&str> {
+        self.registry.keys()
     }
 
-    #[cfg(feature = "fs")]
-    pub fn load_and_gen_niss<const C_SIZE: usize, C: Coord<C_SIZE>>(key: &str, val: &mut Option<NissLookupTable<C_SIZE, C>>, gen_f: &dyn Fn() -> NissLookupTable<C_SIZE, C>, load_f: &dyn Fn() -> Result<NissLookupTable<C_SIZE, C>, String>) -> bool {
-        if val.is_none() {
-            let res = load_f();
-            match res {
-                Ok(v) => {
-                    *val = Some(v);
-                    info!("Loaded {key} table from disk");
-                },
-                Err(e) => {
-                    error!("Error loading {key} table from disk: {e}");
-                }
-            }
-        }
-        if val.is_none() {
-            let table = gen_f();
-            *val = Some(table);
-            return true;
-        }
-        false
+    pub fn gen(&self, key: &str) -> Result<(), String> {
+        self.registry.gen(key)
     }
 
     #[cfg(feature = "fs")]
-    pub fn load_and_save_normal<const C_SIZE: usize, C: Coord<C_SIZE>>(&mut self, key: &str, mut_f: &dyn Fn(&mut Self) -> &mut Option<LookupTable<C_SIZE, C>>, gen_f: &dyn Fn() -> LookupTable<C_SIZE, C>, load_f: &dyn Fn() -> Result<LookupTable<C_SIZE, C>, String>) -> bool {
-        let should_save = Self::load_and_gen_normal(key, mut_f(self), gen_f, load_f);
-        if should_save {
-            if let Err(e) = self.save(key) {
-                error!("Error saving {key} table to disk: {e}");
-            } else {
-                info!("Saved {key} table to disk");
-            }
-        }
-        should_save
+    pub fn save(&self, key: &str) -> std::io::Result<()> {
+        self.registry.save(key)
     }
 
     #[cfg(feature = "fs")]
-    pub fn load_and_save_niss<const C_SIZE: usize, C: Coord<C_SIZE>>(&mut self, key: &str, mut_f: &dyn Fn(&mut Self) -> &mut Option<NissLookupTable<C_SIZE, C>>, gen_f: &dyn Fn() -> NissLookupTable<C_SIZE, C>, load_f: &dyn Fn() -> Result<NissLookupTable<C_SIZE, C>, String>) -> bool {
-        let should_save = Self::load_and_gen_niss(key, mut_f(self), gen_f, load_f);
-        if should_save {
-            if let Err(e) = self.save(key) {
-                error!("Error saving {key} table to disk: {e}");
-            } else {
-                info!("Saved {key} table to disk");
-            }
-        }
-        should_save
-    }
-
-    #[cfg(all(feature = "333eo", feature = "fs"))]
-    pub fn gen_eo(&mut self) {
-        self.load_and_save_normal("eo", &|x|&mut x.eo, &gen_eo, &|| EOPruningTable::load_from_disk("333", "eo"));
+    pub fn load(&self, key: &str) -> Result<(), String> {
+        self.registry.load(key)
     }
 
-    #[cfg(all(feature = "333eo", not(feature = "fs")))]
-    pub fn gen_eo(&mut self) {
-        self.eo = Some(gen_eo());
+    fn get<T: 'static>(&self, key: &str) -> Option<&T> {
+        match self.registry.get(key) {
+            Ok(table) => Some(table),
+            Err(e) => {
+                error!("{e}");
+                None
+            }
+        }
     }
 
     #[cfg(feature = "333eo")]
     pub fn eo(&self) -> Option<&EOPruningTable> {
-        self.eo.as_ref()
-    }
-
-    #[cfg(all(feature = "333dr", feature = "fs"))]
-    pub fn gen_dr(&mut self) {
-        self.load_and_save_normal("dr", &|x|&mut x.dr, &gen_dr, &|| DRPruningTable::load_from_disk("333", "dr"));
-    }
-
-    #[cfg(all(feature = "333dr", not(feature = "fs")))]
-    pub fn gen_dr(&mut self) {
-        self.dr = Some(gen_dr());
+        self.get(EO_KEY)
     }
 
     #[cfg(feature = "333dr")]
     pub fn dr(&self) -> Option<&DRPruningTable> {
-        self.dr.as_ref()
-    }
-
-    #[cfg(all(feature = "333htr", feature = "fs"))]
-    pub fn gen_htr(&mut self) {
-        let new_table = self.load_and_save_niss("htr", &|x|&mut x.htr, &gen_htr, &|| HTRPruningTable::load_from_disk("333", "htr"));
-        if let Some(htr_table) = &mut self.htr {
-            if self.htr_subset.is_some() {
-                return;
-            }
-            match HTRSubsetTable::load_from_disk("333", "htr-subset") {
-                Ok(v) => {
-                    self.htr_subset = Some(v);
-                    info!("Loaded htr-subset table from disk");
-                },
-                Err(e) => {
-                    error!("Error loading htr-subset table from disk: {e}");
-                }
-            }
-            if new_table || self.htr_subset.is_none() {
-                let table = gen_htr_subsets(htr_table);
-                self.htr_subset = Some(table);
-                if let Err(e) = self.save("htr-subset") {
-                    error!("Error saving htr-subset table to disk: {e}");
-                } else {
-                    info!("Saved htr-subset table to disk");
-                }
-                if let Err(e) = self.save("htr") {
-                    error!("Error saving htr table to disk: {e}");
-                } else {
-                    info!("Saved htr table to disk");
-                }
-            }
-        }
-    }
-
-    #[cfg(all(feature = "333htr", not(feature = "fs")))]
-    pub fn gen_htr(&mut self) {
-        self.htr = Some(gen_htr());
+        self.get(DR_KEY)
     }
 
     #[cfg(feature = "333htr")]
     pub fn htr(&self) -> Option<&HTRPruningTable> {
-        self.htr.as_ref()
+        self.get(HTR_KEY)
     }
 
     #[cfg(feature = "333htr")]
     pub fn htr_subset(&self) -> Option<&HTRSubsetTable> {
-        self.htr_subset.as_ref()
+        self.get(HTR_SUBSET_KEY)
     }
 
-    #[cfg(feature = "333htr")]
-    pub fn htr_mut(&mut self) -> Option<&mut HTRPruningTable> {
-        self.htr.as_mut()
+    #[cfg(feature = "333fr")]
+    pub fn fr_leave_slice(&self) -> Option<&FRLeaveSlicePruningTable> {
+        self.get(FRLS_KEY)
     }
 
-    #[cfg(all(feature = "333fr", feature = "fs"))]
-    pub fn gen_fr_leave_slice(&mut self) {
-        self.load_and_save_normal("frls", &|x|&mut x.frls, &gen_fr_leave_slice, &|| FRLeaveSlicePruningTable::load_from_disk("333", "frls"));
+    #[cfg(feature = "333fr")]
+    pub fn fr(&self) -> Option<&FRPruningTable> {
+        self.get(FR_KEY)
     }
 
-    #[cfg(all(feature = "333fr", not(feature = "fs")))]
-    pub fn gen_fr_leave_slice(&mut self) {
-        self.frls = Some(gen_fr_leave_slice());
+    #[cfg(feature = "333finish")]
+    pub fn fr_finish(&self) -> Option<&FRFinishPruningTable> {
+        self.get(FR_FINISH_KEY)
     }
 
-    #[cfg(feature = "333fr")]
-    pub fn fr_leave_slice(&self) -> Option<&FRLeaveSlicePruningTable> {
-        self.frls.as_ref()
+    #[cfg(feature = "333finish")]
+    pub fn htr_finish(&self) -> Option<&HTRFinishPruningTable> {
+        self.get(HTR_FINISH_KEY)
     }
 
-    #[cfg(all(feature = "333fr", feature = "fs"))]
-    pub fn gen_fr(&mut self) {
-        self.load_and_save_normal("fr", &|x|&mut x.fr, &gen_fr, &|| FRPruningTable::load_from_disk("333", "fr"));
+    //Kept from before the registry. Tables are now generated on first access
+    #[cfg(feature = "333eo")]
+    #[deprecated(note = "Tables are generated on first access, use gen(key) to generate them ahead of time")]
+    pub fn gen_eo(&self) {
+        let _ = self.gen(EO_KEY);
     }
 
-    #[cfg(all(feature = "333fr", not(feature = "fs")))]
-    pub fn gen_fr(&mut self) {
-        self.fr = Some(gen_fr());
+    #[cfg(feature = "333dr")]
+    #[deprecated(note = "Tables are generated on first access, use gen(key) to generate them ahead of time")]
+    pub fn gen_dr(&self) {
+        let _ = self.gen(DR_KEY);
     }
 
-    #[cfg(feature = "333fr")]
-    pub fn fr(&self) -> Option<&FRPruningTable> {
-        self.fr.as_ref()
+    #[cfg(feature = "333htr")]
+    #[deprecated(note = "Tables are generated on first access, use gen(key) to generate them ahead of time")]
+    pub fn gen_htr(&self) {
+        let _ = self.gen(HTR_KEY);
     }
 
-    #[cfg(all(feature = "333finish", feature = "fs"))]
-    pub fn gen_fr_finish(&mut self) {
-        self.load_and_save_normal("frfin", &|x|&mut x.fr_finish, &gen_fr_finish, &|| FRFinishPruningTable::load_from_disk("333", "frfin"));
+    #[cfg(feature = "333htr")]
+    #[deprecated(note = "Use registry_mut().get_mut(key)")]
+    pub fn htr_mut(&mut self) -> Option<&mut HTRPruningTable> {
+        self.registry.get_mut(HTR_KEY).ok()
     }
 
-    #[cfg(all(feature = "333finish", not(feature = "fs")))]
-    pub fn gen_fr_finish(&mut self) {
-        self.fr_finish = Some(gen_fr_finish());
+    #[cfg(feature = "333fr")]
+    #[deprecated(note = "Tables are generated on first access, use gen(key) to generate them ahead of time")]
+    pub fn gen_fr_leave_slice(&self) {
+        let _ = self.gen(FRLS_KEY);
     }
 
-    #[cfg(feature = "333finish")]
-    pub fn fr_finish(&self) -> Option<&FRFinishPruningTable> {
-        self.fr_finish.as_ref()
+    #[cfg(feature = "333fr")]
+    #[deprecated(note = "Tables are generated on first access, use gen(key) to generate them ahead of time")]
+    pub fn gen_fr(&self) {
+        let _ = self.gen(FR_KEY);
     }
 
-    #[cfg(all(feature = "333finish", feature = "fs"))]
-    pub fn gen_htr_finish(&mut self) {
-        self.load_and_save_normal("htrfin", &|x|&mut x.htr_finish, &gen_htr_finish, &|| HTRFinishPruningTable::load_from_disk("333", "htrfin"));
+    #[cfg(feature = "333finish")]
+    #[deprecated(note = "Tables are generated on first access, use gen(key) to generate them ahead of time")]
+    pub fn gen_fr_finish(&self) {
+        let _ = self.gen(FR_FINISH_KEY);
     }
 
-    #[cfg(all(feature = "333finish", not(feature = "fs")))]
-    pub fn gen_htr_finish(&mut self) {
-        self.htr_finish = Some(crate::puzzles::c333::steps::tables::gen_htr_finish());
+    #[cfg(feature = "333finish")]
+    #[deprecated(note = "Tables are generated on first access, use gen(key) to generate them ahead of time")]
+    pub fn gen_htr_finish(&self) {
+        let _ = self.gen(HTR_FINISH_KEY);
     }
+}
 
+//Replaces the generators of the small tables with the tables in cubelib/tables
+#[cfg(feature = "embedded_tables")]
+fn register_embedded_tables(registry: &mut TableRegistry) {
+    #[cfg(feature = "333eo")]
+    registry.register_embedded(EO_KEY, embedded::EO, EOPruningTable::from_bytes, gen_eo);
+    #[cfg(feature = "333fr")]
+    registry.register_embedded(FR_KEY, embedded::FR, FRPruningTable::from_bytes, gen_fr);
+    #[cfg(feature = "333fr")]
+    registry.register_embedded(FRLS_KEY, embedded::FRLS, FRLeaveSlicePruningTable::from_bytes, gen_fr_leave_slice);
     #[cfg(feature = "333finish")]
-    pub fn htr_finish(&self) -> Option<&HTRFinishPruningTable> {
-        self.htr_finish.as_ref()
-    }
+    registry.register_embedded(FR_FINISH_KEY, embedded::FR_FINISH, FRFinishPruningTable::from_bytes, gen_fr_finish);
 }
 
 #[cfg(feature = "333eo")]
@@ -370,7 +242,7 @@ fn gen_eo() -> EOPruningTable {
     table
 }
 
-#[cfg(feature = "333dr")]
+#[cfg(all(feature = "333dr", not(feature = "sym_tables")))]
 fn gen_dr() -> DRPruningTable {
     info!("Generating DR pruning table...");
     #[cfg(not(target_arch = "wasm32"))]
@@ -385,7 +257,7 @@ fn gen_dr() -> DRPruningTable {
     table
 }
 
-#[cfg(feature = "333htr")]
+#[cfg(all(feature = "333htr", not(feature = "sym_tables")))]
 fn gen_htr() -> HTRPruningTable {
     info!("Generating HTR pruning table...");
     #[cfg(not(target_arch = "wasm32"))]
@@ -401,12 +273,11 @@ fn gen_htr() -> HTRPruningTable {
 }
 
 #[cfg(feature = "333htr")]
-#[cfg(feature = "fs")]
-fn gen_htr_subsets(htr_table: &mut HTRPruningTable) -> HTRSubsetTable {
+fn gen_htr_subsets() -> HTRSubsetTable {
     info!("Generating HTR subset table...");
     #[cfg(not(target_arch = "wasm32"))]
     let time = Instant::now();
-    let subset_table = crate::puzzles::c333::steps::htr::subsets::gen_subset_tables(htr_table);
+    let subset_table = crate::puzzles::c333::steps::htr::subsets::gen_subset_table();
     #[cfg(not(target_arch = "wasm32"))]
     debug!("Took {}ms", time.elapsed().as_millis());
     subset_table
@@ -457,7 +328,7 @@ fn gen_fr_finish() -> FRFinishPruningTable {
     table
 }
 
-#[cfg(feature = "333finish")]
+#[cfg(all(feature = "333finish", not(feature = "sym_tables")))]
 fn gen_htr_finish() -> HTRFinishPruningTable {
     info!("Generating HTR finish pruning table...");
     #[cfg(not(target_arch = "wasm32"))]
@@ -470,4 +341,181 @@ fn gen_htr_finish() -> HTRFinishPruningTable {
     #[cfg(not(target_arch = "wasm32"))]
     debug!("Took {}ms", time.elapsed().as_millis());
     table
-}
\ No newline at end of file
+}
+
+#[cfg(all(feature = "333dr", feature = "sym_tables"))]
+fn gen_dr() -> DRPruningTable {
+    info!("Generating symmetry reduced DR pruning table...");
+    #[cfg(not(target_arch = "wasm32"))]
+    let time = Instant::now();
+    let table = lookup_table::generate_sym(&DR_UD_EO_FB_MOVESET,
+                                           &|c: &crate::puzzles::c333::Cube333| DRUDEOFBCoord::from(c));
+    #[cfg(not(target_arch = "wasm32"))]
+    debug!("Took {}ms", time.elapsed().as_millis());
+    table
+}
+
+#[cfg(all(feature = "333finish", feature = "sym_tables"))]
+fn gen_htr_finish() -> HTRFinishPruningTable {
+    info!("Generating symmetry reduced HTR finish pruning table...");
+    #[cfg(not(target_arch = "wasm32"))]
+    let time = Instant::now();
+    let table = lookup_table::generate_sym(&HTR_FINISH_MOVESET,
+                                           &|c: &crate::puzzles::c333::Cube333| HTRFinishCoord::from(c));
+    #[cfg(not(target_arch = "wasm32"))]
+    debug!("Took {}ms", time.elapsed().as_millis());
+    table
+}
+
+//The NISS values are filled in from the subset table afterwards, see PruningTables333::new
+#[cfg(all(feature = "333htr", feature = "sym_tables"))]
+fn gen_htr() -> HTRPruningTable {
+    info!("Generating symmetry reduced HTR pruning table...");
+    #[cfg(not(target_arch = "wasm32"))]
+    let time = Instant::now();
+    let table = lookup_table::generate_sym(&HTR_DR_UD_MOVESET,
+                                           &|c: &crate::puzzles::c333::Cube333| HTRDRUDCoord::from(c));
+    #[cfg(not(target_arch = "wasm32"))]
+    debug!("Took {}ms", time.elapsed().as_millis());
+    table.into_niss()
+}
+
+#[cfg(all(test, feature = "333dr"))]
+mod tests {
+    use std::collections::HashSet;
+    use std::fmt::Debug;
+
+    use crate::puzzles::c333::{Cube333, Transformation333};
+    use crate::puzzles::c333::steps::MoveSet333;
+    use crate::puzzles::puzzle::{PuzzleMove, TransformableMut, TurnableMut};
+    use crate::solver::lookup_table::{LookupTable, SymLookupTable, TableLookup, with_generation_threads};
+    use crate::steps::coord::SymCoord;
+
+    use super::*;
+
+    fn assert_thread_independent<T>(generator: fn() -> T) where for<'a> &'a T: Into<Vec<u8>> {
+        let single: Vec<u8> = (&with_generation_threads(1, generator)).into();
+        let multi: Vec<u8> = (&with_generation_threads(8, generator)).into();
+        assert!(single == multi, "Tables generated with 1 and 8 threads differ");
+    }
+
+    #[test]
+    fn eo_table_is_thread_independent() {
+        assert_thread_independent(gen_eo);
+    }
+
+    #[test]
+    fn dr_table_is_thread_independent() {
+        assert_thread_independent(gen_dr);
+    }
+
+    //The embedded files are checked in and have to match what the generators produce at runtime
+    #[test]
+    #[cfg(feature = "embedded_tables")]
+    fn embedded_tables_match_generated_tables() {
+        let tables = PruningTables333::new();
+        let registry = tables.registry();
+        let keys: Vec<&str> = registry.keys().into_iter().filter(|key| registry.is_embedded(key)).collect();
+        assert!(!keys.is_empty());
+        for key in keys {
+            let embedded = registry.embedded_bytes(key).unwrap();
+            assert!(embedded == registry.generate_bytes(key).unwrap().as_slice(), "Embedded {key} table differs from the generated table");
+        }
+    }
+
+    //Visits every coordinate that can be reached from the solved cube and compares the two tables on one state per coordinate.
+    //The transformations have to keep the cube inside the domain of the coordinate
+    fn assert_sym_matches_full<const SIZE: usize, C>(move_set: &MoveSet333, transformations: &[Transformation333])
+        where C: SymCoord<SIZE, Cube333> + for<'x> From<&'x Cube333> + Debug + Send + Sync {
+        let full: LookupTable<SIZE, C> = lookup_table::generate(move_set,
+                                                                &|c: &Cube333| C::from(c),
+                                                                &|| LookupTable::new(false),
+                                                                &|table, coord| table.get(coord),
+                                                                &|table, coord, val| table.set(coord, val));
+        let sym: SymLookupTable<SIZE, C> = lookup_table::generate_sym(move_set, &|c: &Cube333| C::from(c));
+        assert!(sym.class_count() < SIZE);
+
+        let start = Cube333::default();
+        let mut visited = HashSet::from([C::from(&start)]);
+        let mut to_check = vec![start];
+        while !to_check.is_empty() {
+            let mut check_next = vec![];
+            for cube in to_check {
+                let turns = move_set.aux_moves.iter().chain(move_set.st_moves.iter()).map(|m| {
+                    let mut cube = cube;
+                    cube.turn(*m);
+                    cube
+                });
+                let transformations = transformations.iter().map(|t| {
+                    let mut cube = cube;
+                    cube.transform(*t);
+                    cube
+                });
+                for next in turns.chain(transformations) {
+                    let coord = C::from(&next);
+                    if !visited.insert(coord) {
+                        continue;
+                    }
+                    assert_eq!(full.get(coord), sym.lookup(&next), "Different values for {coord:?}");
+                    assert_eq!(sym.get(coord).is_some(), C::representative(&next) == coord, "{coord:?} is stored incorrectly");
+                    check_next.push(next);
+                }
+            }
+            to_check = check_next;
+        }
+        assert_eq!(visited.len(), SIZE);
+    }
+
+    #[test]
+    fn dr_sym_table_matches_full_table() {
+        assert_sym_matches_full::<{ crate::puzzles::c333::steps::dr::coords::DRUDEOFB_SIZE }, DRUDEOFBCoord>(&DR_UD_EO_FB_MOVESET, Transformation333::all());
+    }
+
+    #[cfg(feature = "333htr")]
+    #[test]
+    fn htr_sym_table_matches_full_table() {
+        assert_sym_matches_full::<{ crate::puzzles::c333::steps::htr::coords::HTRDRUD_SIZE }, HTRDRUDCoord>(&HTR_DR_UD_MOVESET, &[]);
+    }
+
+    //Every class keeps the smallest NISS value of its states, so the value can't exceed the one of the state's own subset
+    #[cfg(all(feature = "333htr", feature = "sym_tables"))]
+    #[test]
+    fn htr_sym_table_niss_values_are_lower_bounds() {
+        use crate::solver::lookup_table::NissTableLookup;
+
+        let tables = PruningTables333::new();
+        let htr = tables.htr().unwrap();
+        let subsets = tables.htr_subset().unwrap();
+        let niss_bounds = crate::puzzles::c333::steps::htr::subsets::niss_bounds();
+        let start = Cube333::default();
+        let mut visited = HashSet::from([HTRDRUDCoord::from(&start)]);
+        let mut to_check = vec![start];
+        while !to_check.is_empty() {
+            let mut check_next = vec![];
+            for cube in to_check {
+                let (val, niss) = htr.lookup_niss(&cube);
+                assert_eq!(val, htr.lookup(&cube));
+                let subset = subsets.get(HTRDRUDCoord::from(&cube));
+                assert!(niss <= niss_bounds[subset as usize], "NISS value {niss} is larger than the bound of subset {subset}");
+                let mut mirrored = cube;
+                mirrored.mirror(crate::puzzles::cube::CubeAxis::LR);
+                assert_eq!((val, niss), htr.lookup_niss(&mirrored));
+                for m in HTR_DR_UD_MOVESET.aux_moves.iter().chain(HTR_DR_UD_MOVESET.st_moves.iter()) {
+                    let mut next = cube;
+                    next.turn(*m);
+                    if visited.insert(HTRDRUDCoord::from(&next)) {
+                        check_next.push(next);
+                    }
+                }
+            }
+            to_check = check_next;
+        }
+        assert_eq!(visited.len(), crate::puzzles::c333::steps::htr::coords::HTRDRUD_SIZE);
+    }
+
+    #[cfg(feature = "333finish")]
+    #[test]
+    fn htr_finish_sym_table_matches_full_table() {
+        assert_sym_matches_full::<{ crate::puzzles::c333::steps::finish::coords::HTR_FINISH_SIZE }, HTRFinishCoord>(&HTR_FINISH_MOVESET, Transformation333::all());
+    }
+}
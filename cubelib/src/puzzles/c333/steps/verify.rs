@@ -0,0 +1,197 @@
+use std::str::FromStr;
+
+use crate::algs::Algorithm;
+use crate::defs::StepKind;
+use crate::puzzles::c333::{Cube333, Turn333};
+use crate::puzzles::c333::steps::analysis::{self, Subgroup};
+use crate::puzzles::cube::{CubeOuterTurn, Direction};
+use crate::puzzles::puzzle::ApplyAlgorithm;
+use crate::solver::solution::{ApplySolution, Solution, SolutionStep};
+
+const EDGE_NAMES: [&str; 12] = ["UB", "UR", "UF", "UL", "FR", "FL", "BR", "BL", "DF", "DR", "DB", "DL"];
+const CORNER_NAMES: [&str; 8] = ["UBL", "UBR", "UFR", "UFL", "DFL", "DFR", "DBR", "DBL"];
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerifiedStep {
+    pub step: SolutionStep<Turn333>,
+    pub length: usize,
+    pub total_length: usize,
+    //The subgroup after applying this and all previous steps
+    pub subgroup: Subgroup,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(serde::Serialize, serde::Deserialize))]
+pub struct Verification {
+    pub solved: bool,
+    pub length: usize,
+    //The solution without NISS and with all cancelling moves merged
+    pub cancelled: Algorithm<Turn333>,
+    pub cancelled_length: usize,
+    pub steps: Vec<VerifiedStep>,
+    pub subgroup: Subgroup,
+    //Pieces that aren't in their solved position or orientation, by the name of the position they belong in
+    pub unsolved_edges: Vec<String>,
+    pub unsolved_corners: Vec<String>,
+}
+
+//Parses a solution that is either a single algorithm, or one step per line in the format `alg // label`.
+//Labels like `dr-ud-eolr` are split into the step kind and variant. Anything after the label, like the move counts the CLI prints, is ignored.
+pub fn parse_solution(solution: &str) -> Result<Solution<Turn333>, String> {
+    let mut parsed = Solution::new();
+    for (n, line) in solution.lines().enumerate() {
+        let (alg, label) = match line.split_once("//") {
+            Some((alg, label)) => (alg.trim(), label.split_whitespace().next()),
+            None => (line.trim(), None),
+        };
+        //The summary line of the CLI output repeats the whole solution
+        if alg.is_empty() || alg.starts_with("Solution") {
+            continue;
+        }
+        let alg = Algorithm::<Turn333>::from_str(alg).map_err(|_| format!("Invalid algorithm '{alg}' on line {}", n + 1))?;
+        let (kind, variant) = match label {
+            Some(label) => {
+                let (kind, variant) = label.split_once('-').unwrap_or((label, ""));
+                (StepKind::from_str(kind)?, variant.to_string())
+            },
+            None => (StepKind::Other(String::new()), String::new()),
+        };
+        parsed.add_step(SolutionStep {
+            kind,
+            variant,
+            alg,
+        });
+    }
+    if parsed.steps.is_empty() {
+        return Err("The solution is empty".to_string());
+    }
+    Ok(parsed)
+}
+
+pub fn verify(scramble: &Algorithm<Turn333>, solution: &Solution<Turn333>) -> Verification {
+    let mut scrambled = Cube333::default();
+    scrambled.apply_alg(scramble);
+
+    let mut partial = Solution::new();
+    let mut steps = vec![];
+    let mut cube = scrambled;
+    for step in solution.steps.iter() {
+        partial.add_step(step.clone());
+        cube = scrambled;
+        cube.apply_solution(&partial);
+        steps.push(VerifiedStep {
+            step: step.clone(),
+            length: step.alg.len(),
+            total_length: partial.len(),
+            subgroup: analysis::subgroup(&cube),
+        });
+    }
+
+    let unsolved_edges = cube.edges.get_edges().iter()
+        .enumerate()
+        .filter(|(pos, edge)| edge.id as usize != *pos || !edge.oriented_fb)
+        .map(|(pos, _)| EDGE_NAMES[pos].to_string())
+        .collect();
+    let unsolved_corners = cube.corners.get_corners().iter()
+        .enumerate()
+        .filter(|(pos, corner)| corner.id as usize != *pos || corner.orientation != 0)
+        .map(|(pos, _)| CORNER_NAMES[pos].to_string())
+        .collect();
+    let subgroup = analysis::subgroup(&cube);
+    let linear: Algorithm<Turn333> = Into::<Algorithm<Turn333>>::into(solution.clone()).to_uninverted();
+    let cancelled = Algorithm {
+        normal_moves: cancel_moves(&linear.normal_moves),
+        inverse_moves: vec![],
+    };
+    Verification {
+        solved: subgroup == Subgroup::Solved,
+        length: solution.len(),
+        cancelled_length: cancelled.len(),
+        cancelled,
+        steps,
+        subgroup,
+        unsolved_edges,
+        unsolved_corners,
+    }
+}
+
+//Merges consecutive moves on the same face, including moves separated by turns of the opposite face, e.g. R L R2 becomes R' L
+pub fn cancel_moves(moves: &[Turn333]) -> Vec<Turn333> {
+    let mut cancelled: Vec<Turn333> = vec![];
+    for m in moves {
+        let same_axis = cancelled.iter()
+            .rev()
+            .take_while(|prev| prev.face == m.face || prev.face == m.face.opposite())
+            .position(|prev| prev.face == m.face)
+            .map(|pos| cancelled.len() - 1 - pos);
+        match same_axis {
+            Some(pos) => {
+                let quarter_turns = (quarter_turns(cancelled[pos].dir) + quarter_turns(m.dir)) % 4;
+                match quarter_turns {
+                    0 => {
+                        cancelled.remove(pos);
+                    },
+                    1 => cancelled[pos] = CubeOuterTurn::new(m.face, Direction::Clockwise),
+                    2 => cancelled[pos] = CubeOuterTurn::new(m.face, Direction::Half),
+                    _ => cancelled[pos] = CubeOuterTurn::new(m.face, Direction::CounterClockwise),
+                }
+            },
+            None => cancelled.push(*m),
+        }
+    }
+    cancelled
+}
+
+fn quarter_turns(dir: Direction) -> u8 {
+    match dir {
+        Direction::Clockwise => 1,
+        Direction::Half => 2,
+        Direction::CounterClockwise => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cancel(alg: &str) -> String {
+        let alg = Algorithm::<Turn333>::from_str(alg).unwrap();
+        Algorithm {
+            normal_moves: cancel_moves(&alg.normal_moves),
+            inverse_moves: vec![],
+        }.to_string()
+    }
+
+    #[test]
+    fn merges_moves_on_the_same_face() {
+        assert_eq!(cancel("R R2"), "R'");
+        assert_eq!(cancel("R R"), "R2");
+        assert_eq!(cancel("U R2 R2 F"), "U F");
+        assert_eq!(cancel("R U F"), "R U F");
+    }
+
+    #[test]
+    fn merges_moves_separated_by_the_opposite_face() {
+        assert_eq!(cancel("R L R2"), "R' L");
+        assert_eq!(cancel("U D U' D2"), "D'");
+        assert_eq!(cancel("R U L R"), "R U L R");
+    }
+
+    #[test]
+    fn cancels_chains_of_moves() {
+        assert_eq!(cancel("U R R' U'"), "");
+        assert_eq!(cancel("F U R L2 L2 R' U' B"), "F B");
+    }
+
+    #[test]
+    fn cancels_moves_across_niss() {
+        let scramble = Algorithm::from_str("R U").unwrap();
+        let solution = parse_solution("U' R' R2 // eo\n(R2) // dr").unwrap();
+        let verification = verify(&scramble, &solution);
+        assert!(verification.solved);
+        assert_eq!(verification.length, 4);
+        assert_eq!(verification.cancelled.to_string(), "U' R'");
+        assert_eq!(verification.cancelled_length, 2);
+    }
+}
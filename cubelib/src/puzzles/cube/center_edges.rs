@@ -86,6 +86,60 @@ impl InvertibleMut for CenterEdgeCube {
 }
 
 impl CenterEdgeCube {
+    //Conjugates the cube with the reflection that swaps the L and R faces. Edges keep their orientation on every axis
+    #[inline]
+    #[cfg(target_feature = "avx2")]
+    pub fn mirror_lr(&mut self) {
+        unsafe { avx2::unsafe_mirror_lr(self) }
+    }
+
+    #[inline]
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
+    pub fn mirror_lr(&mut self) {
+        wasm32::mirror_lr(self)
+    }
+
+    //One byte per edge with the id in the upper 4 bits, followed by one bit per axis (UD, FB, RL) that is set if the edge
+    //is flipped on that axis. Fails if the edge ids aren't a permutation or unused bits are set
+    pub fn from_bytes(bytes: [u8; 16]) -> Result<CenterEdgeCube, String> {
+        let mut seen = [false; 12];
+        for (pos, byte) in bytes.iter().enumerate() {
+            if pos >= 12 {
+                if *byte != 0 {
+                    return Err(format!("Unused edge byte {pos} is set"));
+                }
+                continue;
+            }
+            let id = (byte >> 4) as usize;
+            if id >= 12 || byte & 1 != 0 {
+                return Err(format!("Invalid edge {byte:#010b} at position {pos}"));
+            }
+            if seen[id] {
+                return Err(format!("Edge {id} appears more than once"));
+            }
+            seen[id] = true;
+        }
+        Ok(Self::load_bytes(bytes))
+    }
+
+    #[cfg(target_feature = "avx2")]
+    fn load_bytes(bytes: [u8; 16]) -> CenterEdgeCube {
+        CenterEdgeCube(unsafe { std::arch::x86_64::_mm_loadu_si128(bytes.as_ptr() as *const std::arch::x86_64::__m128i) })
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
+    fn load_bytes(bytes: [u8; 16]) -> CenterEdgeCube {
+        CenterEdgeCube(unsafe { std::arch::wasm32::v128_load(bytes.as_ptr() as *const std::arch::wasm32::v128) })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let raw = self.get_edges_raw();
+        let mut bytes = [0_u8; 16];
+        bytes[0..8].copy_from_slice(&raw[0].to_le_bytes());
+        bytes[8..16].copy_from_slice(&raw[1].to_le_bytes());
+        bytes
+    }
+
     #[cfg(target_feature = "avx2")]
     pub fn new(state: std::arch::x86_64::__m128i) -> CenterEdgeCube {
         CenterEdgeCube(state)
@@ -116,14 +170,7 @@ impl CenterEdgeCube {
 impl serde::Serialize for CenterEdgeCube {
 
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
-        let bytes = [0_u8; 16];
-        unsafe {
-            #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
-            std::arch::wasm32::v128_store(bytes.as_ptr() as *mut std::arch::wasm32::v128, self.0);
-            #[cfg(target_feature = "avx2")]
-            std::arch::x86_64::_mm_store_si128(bytes.as_ptr() as *mut std::arch::x86_64::__m128i, self.0);
-        }
-        serializer.serialize_bytes(&bytes)
+        serializer.serialize_bytes(&self.to_bytes())
     }
 }
 
@@ -142,14 +189,7 @@ impl<'de> serde::de::Visitor<'de> for EdgeCubieCubeVisitor {
         if v.len() != 16 {
             Err(E::custom("Array length must be 16"))
         } else {
-            let val = unsafe {
-                #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
-                    let val = std::arch::wasm32::v128_load(v.as_ptr() as *const std::arch::wasm32::v128);
-                #[cfg(target_feature = "avx2")]
-                    let val = std::arch::x86_64::_mm_load_si128(v.as_ptr() as *const std::arch::x86_64::__m128i);
-                val
-            };
-            Ok(CenterEdgeCube(val))
+            CenterEdgeCube::from_bytes(v.try_into().unwrap()).map_err(E::custom)
         }
     }
 }
@@ -348,6 +388,17 @@ mod avx2 {
         cube.0 = _mm_or_si128(ep_translated, eo);
     }
 
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    pub(crate) unsafe fn unsafe_mirror_lr(cube: &mut CenterEdgeCube) {
+        //The reflection swaps UR-UL, FR-FL, BR-BL and DR-DL. The same shuffle translates the edge ids
+        let shuffle = _mm_setr_epi8(0, 3, 2, 1, 5, 4, 7, 6, 8, 11, 10, 9, -1, -1, -1, -1);
+        let mirrored = _mm_shuffle_epi8(cube.0, shuffle);
+        let ids = _mm_and_si128(_mm_srli_epi32::<4>(mirrored), _mm_set1_epi8(0b00001111));
+        let ids = _mm_slli_epi32::<4>(_mm_shuffle_epi8(shuffle, ids));
+        cube.0 = _mm_or_si128(ids, _mm_and_si128(mirrored, _mm_set1_epi8(0b00001111)));
+    }
+
     // TODO[perf]
     // We could speed this up by a factor of 10 if changed the cube representation to
     //__m256(normal, inverse) and just swapped the hi and lo parts.
@@ -540,6 +591,15 @@ mod wasm32 {
         cube.0 = v128_or(ep_translated, eo);
     }
 
+    #[inline]
+    pub(crate) fn mirror_lr(cube: &mut CenterEdgeCube) {
+        let shuffle = u8x16(0, 3, 2, 1, 5, 4, 7, 6, 8, 11, 10, 9, 0xFF, 0xFF, 0xFF, 0xFF);
+        let mirrored = u8x16_swizzle(cube.0, shuffle);
+        let ids = v128_and(u32x4_shr(mirrored, 4), u8x16_set1(0b00001111));
+        let ids = u32x4_shl(u8x16_swizzle(shuffle, ids), 4);
+        cube.0 = v128_or(ids, v128_and(mirrored, u8x16_set1(0b00001111)));
+    }
+
     pub(crate) fn invert(cube: &mut CenterEdgeCube) {
         let edge_ids = unsafe {
             let mut a_arr = [0u8; 16];
@@ -82,6 +82,58 @@ impl InvertibleMut for CubeCornersOdd {
 }
 
 impl CubeCornersOdd {
+    //Conjugates the cube with the reflection that swaps the L and R faces
+    #[inline]
+    #[cfg(target_feature = "avx2")]
+    pub fn mirror_lr(&mut self) {
+        unsafe { avx2::unsafe_mirror_lr(self) }
+    }
+
+    #[inline]
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
+    pub fn mirror_lr(&mut self) {
+        wasm32::mirror_lr(self)
+    }
+
+    //One byte per corner with the id in the upper 3 bits and the orientation in the lower 2. Fails if the corner ids
+    //aren't a permutation, an orientation is out of range or unused bits are set
+    pub fn from_bytes(bytes: [u8; 16]) -> Result<CubeCornersOdd, String> {
+        let mut seen = [false; 8];
+        for (pos, byte) in bytes.iter().enumerate() {
+            if pos >= 8 {
+                if *byte != 0 {
+                    return Err(format!("Unused corner byte {pos} is set"));
+                }
+                continue;
+            }
+            let id = (byte >> 5) as usize;
+            if byte & 0b11100 != 0 || byte & 0b11 > 2 {
+                return Err(format!("Invalid corner {byte:#010b} at position {pos}"));
+            }
+            if seen[id] {
+                return Err(format!("Corner {id} appears more than once"));
+            }
+            seen[id] = true;
+        }
+        Ok(Self::load_bytes(bytes))
+    }
+
+    #[cfg(target_feature = "avx2")]
+    fn load_bytes(bytes: [u8; 16]) -> CubeCornersOdd {
+        CubeCornersOdd(unsafe { std::arch::x86_64::_mm_loadu_si128(bytes.as_ptr() as *const std::arch::x86_64::__m128i) })
+    }
+
+    #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
+    fn load_bytes(bytes: [u8; 16]) -> CubeCornersOdd {
+        CubeCornersOdd(unsafe { std::arch::wasm32::v128_load(bytes.as_ptr() as *const std::arch::wasm32::v128) })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0_u8; 16];
+        bytes[0..8].copy_from_slice(&self.get_corners_raw().to_le_bytes());
+        bytes
+    }
+
     #[cfg(target_feature = "avx2")]
     pub fn new(state: std::arch::x86_64::__m128i) -> CubeCornersOdd {
         CubeCornersOdd(state)
@@ -116,14 +168,7 @@ impl CubeCornersOdd {
 impl serde::Serialize for CubeCornersOdd {
 
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
-        let bytes = [0_u8; 16];
-        unsafe {
-            #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
-            std::arch::wasm32::v128_store(bytes.as_ptr() as *mut std::arch::wasm32::v128, self.0);
-            #[cfg(target_feature = "avx2")]
-            std::arch::x86_64::_mm_store_si128(bytes.as_ptr() as *mut std::arch::x86_64::__m128i, self.0);
-        }
-        serializer.serialize_bytes(&bytes)
+        serializer.serialize_bytes(&self.to_bytes())
     }
 }
 
@@ -142,14 +187,7 @@ impl<'de> serde::de::Visitor<'de> for CornerCubieCubeVisitor {
         if v.len() != 16 {
             Err(E::custom("Array length must be 16"))
         } else {
-            let val = unsafe {
-                #[cfg(all(target_arch = "wasm32", not(target_feature = "avx2")))]
-                    let val = std::arch::wasm32::v128_load(v.as_ptr() as *const std::arch::wasm32::v128);
-                #[cfg(target_feature = "avx2")]
-                    let val = std::arch::x86_64::_mm_load_si128(v.as_ptr() as *const std::arch::x86_64::__m128i);
-                val
-            };
-            Ok(CubeCornersOdd(val))
+            CubeCornersOdd::from_bytes(v.try_into().unwrap()).map_err(E::custom)
         }
     }
 }
@@ -350,6 +388,23 @@ mod avx2 {
         cube.0 = _mm_or_si128(cp_translated, co);
     }
 
+    #[target_feature(enable = "avx2")]
+    #[inline]
+    pub(crate) unsafe fn unsafe_mirror_lr(cube: &mut CubeCornersOdd) {
+        //Every corner swaps places with its neighbour on the other side, which is also the corner whose id differs in the lowest bit
+        let mirrored = _mm_shuffle_epi8(cube.0, _mm_setr_epi8(1, 0, 3, 2, 5, 4, 7, 6, -1, -1, -1, -1, -1, -1, -1, -1));
+        let cp = _mm_xor_si128(
+            _mm_and_si128(mirrored, _mm_set1_epi8(0b11100000_u8 as i8)),
+            _mm_setr_epi8(0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0, 0, 0, 0, 0, 0, 0, 0),
+        );
+        //A reflection reverses the direction of every twist
+        let co = _mm_shuffle_epi8(
+            _mm_setr_epi8(0, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+            _mm_and_si128(mirrored, _mm_set1_epi8(0b00000011)),
+        );
+        cube.0 = _mm_or_si128(cp, co);
+    }
+
     #[target_feature(enable = "avx2")]
     #[inline]
     pub(crate) unsafe fn unsafe_invert(cube: &mut CubeCornersOdd) {
@@ -550,6 +605,20 @@ mod wasm32 {
         cube.0 = v128_or(cp_translated, co);
     }
 
+    #[inline]
+    pub(crate) fn mirror_lr(cube: &mut CubeCornersOdd) {
+        let mirrored = u8x16_swizzle(cube.0, u8x16(1, 0, 3, 2, 5, 4, 7, 6, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF));
+        let cp = v128_xor(
+            v128_and(mirrored, u8x16_set1(0b11100000)),
+            u8x16(0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0b00100000, 0, 0, 0, 0, 0, 0, 0, 0),
+        );
+        let co = u8x16_swizzle(
+            u8x16(0, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0),
+            v128_and(mirrored, u8x16_set1(0b00000011)),
+        );
+        cube.0 = v128_or(cp, co);
+    }
+
     #[inline]
     pub(crate) fn invert(cube: &mut CubeCornersOdd) {
         let corner_ids =
@@ -402,7 +402,7 @@ impl<T, const N: usize> Index<CubeAxis> for [T; N] {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CubeColor {
     White = 0,
     Yellow = 1,
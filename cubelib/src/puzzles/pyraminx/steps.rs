@@ -124,10 +124,13 @@ pub mod tables {
     #[cfg(feature = "pyraminxfinish")]
     use crate::solver::lookup_table;
     use crate::solver::lookup_table::TableType;
+    use crate::solver::table_registry::TableRegistry;
 
+    pub const FIN_KEY: &str = "fin";
+
+    #[derive(Clone)]
     pub struct PruningTablesPyraminx {
-        #[cfg(feature = "pyraminxfinish")]
-        direct_finish: Option<NoTipsFinishPruningTable>
+        registry: TableRegistry,
     }
 
     impl PruningTablesPyraminx {
@@ -135,32 +138,35 @@ pub mod tables {
         pub const VERSION: u32 = 1;
 
         pub fn new() -> PruningTablesPyraminx {
+            let mut registry = TableRegistry::new("pyraminx");
+            #[cfg(feature = "pyraminxfinish")]
+            registry.register(FIN_KEY, gen_finish_no_tips);
             PruningTablesPyraminx {
-                #[cfg(feature = "pyraminxfinish")]
-                direct_finish: None
+                registry
             }
         }
 
-        pub fn load(&mut self, key: &str, data: Box<Vec<u8>>) -> Result<(), String> {
-            match key {
-                #[cfg(feature = "pyraminxfinish")]
-                "fin" => self.direct_finish = Some(NoTipsFinishPruningTable::load(data)?),
-                _ => {}
-            }
-            Ok(())
+        pub fn registry(&self) -> &TableRegistry {
+            &self.registry
+        }
+
+        pub fn registry_mut(&mut self) -> &mut TableRegistry {
+            &mut self.registry
+        }
+
+        #[cfg(feature = "fs")]
+        pub fn load(&self, key: &str, data: Box<Vec<u8>>) -> Result<(), String> {
+            self.registry.load_bytes(key, *data)
         }
 
         #[cfg(feature = "pyraminxfinish")]
-        pub fn gen_finish_no_tips(&mut self) {
-            if self.direct_finish.is_none() {
-                let table = gen_finish_no_tips();
-                self.direct_finish = Some(table);
-            }
+        pub fn gen_finish_no_tips(&self) {
+            let _ = self.registry.gen(FIN_KEY);
         }
 
         #[cfg(feature = "pyraminxfinish")]
         pub fn fin(&self) -> Option<&NoTipsFinishPruningTable> {
-            self.direct_finish.as_ref()
+            self.registry.get(FIN_KEY).ok()
         }
     }
 
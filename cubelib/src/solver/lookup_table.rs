@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Debug;
 #[cfg(feature = "fs")]
 use std::fs;
@@ -9,14 +11,14 @@ use std::hash::Hash;
 use std::io::{Read, Write};
 use std::marker::PhantomData;
 #[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "fs")]
 use home::home_dir;
 use log::{debug, warn};
-use num_traits::{ToPrimitive};
-#[cfg(feature = "fs")]
-use num_traits::{FromPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
 use crate::solver::moveset::{MoveSet, TransitionTable};
 use crate::puzzles::puzzle::{Puzzle, PuzzleMove, Transformable};
-use crate::steps::coord::Coord;
+use crate::steps::coord::{Coord, SymCoord};
 
 const VERSION: u8 = 1;
 
@@ -27,6 +29,8 @@ pub enum TableType {
     Uncompressed = 0u8,
     Compressed = 1u8,
     Niss = 2u8,
+    Symmetry = 3u8,
+    SymmetryNiss = 4u8,
 }
 
 #[derive(Clone)]
@@ -42,6 +46,61 @@ pub struct NissLookupTable<const C_SIZE: usize, C: Coord<C_SIZE>> {
     coord_type: PhantomData<C>,
 }
 
+//Stores one entry per symmetry class. The representatives bitmap has a bit set for every coordinate that
+//is the smallest one in its class, and the index of a class is the number of representatives before it.
+//With niss set, the upper 4 bits of every entry hold a NISS value like in NissLookupTable.
+#[derive(Clone)]
+pub struct SymLookupTable<const C_SIZE: usize, C: Coord<C_SIZE>> {
+    representatives: Box<[u64]>,
+    ranks: Box<[u32]>,
+    entries: Box<[u8]>,
+    coord_type: PhantomData<C>,
+    niss: bool,
+}
+
+//Number of bitmap words that share one precomputed rank
+const SYM_RANK_BLOCK: usize = 8;
+
+pub trait TableLookup<PuzzleParam> {
+    fn lookup(&self, puzzle: &PuzzleParam) -> u8;
+}
+
+//Returns the pruning value and the NISS value of a state
+pub trait NissTableLookup<PuzzleParam> {
+    fn lookup_niss(&self, puzzle: &PuzzleParam) -> (u8, u8);
+}
+
+impl <const C_SIZE: usize, C: Coord<C_SIZE>, PuzzleParam> TableLookup<PuzzleParam> for LookupTable<C_SIZE, C> where C: for<'x> From<&'x PuzzleParam> {
+    fn lookup(&self, puzzle: &PuzzleParam) -> u8 {
+        self.get(C::from(puzzle))
+    }
+}
+
+impl <const C_SIZE: usize, C: Coord<C_SIZE>, PuzzleParam> TableLookup<PuzzleParam> for SymLookupTable<C_SIZE, C> where C: SymCoord<C_SIZE, PuzzleParam> {
+    fn lookup(&self, puzzle: &PuzzleParam) -> u8 {
+        let representative = C::representative(puzzle);
+        let val = self.get(representative);
+        //Only a table that doesn't match its coordinate can miss a representative. 0 is always a valid lower bound
+        debug_assert!(val.is_some(), "{:?} is not a representative", representative.val());
+        val.unwrap_or(0)
+    }
+}
+
+impl <const C_SIZE: usize, C: Coord<C_SIZE>, PuzzleParam> NissTableLookup<PuzzleParam> for NissLookupTable<C_SIZE, C> where C: for<'x> From<&'x PuzzleParam> {
+    fn lookup_niss(&self, puzzle: &PuzzleParam) -> (u8, u8) {
+        self.get(C::from(puzzle))
+    }
+}
+
+impl <const C_SIZE: usize, C: Coord<C_SIZE>, PuzzleParam> NissTableLookup<PuzzleParam> for SymLookupTable<C_SIZE, C> where C: SymCoord<C_SIZE, PuzzleParam> {
+    fn lookup_niss(&self, puzzle: &PuzzleParam) -> (u8, u8) {
+        let representative = C::representative(puzzle);
+        let val = self.get_niss(representative);
+        debug_assert!(val.is_some(), "{:?} is not a representative", representative.val());
+        val.unwrap_or((0, 0))
+    }
+}
+
 impl <const C_SIZE: usize, C: Coord<C_SIZE>> Into<Vec<u8>> for &LookupTable<C_SIZE, C> {
     fn into(self) -> Vec<u8> {
         let table_type = if self.compressed {
@@ -88,6 +147,29 @@ impl<const C_SIZE: usize, C: Coord<C_SIZE>> LookupTable<C_SIZE, C> {
         ser
     }
 
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        if data.len() < 2 || data[0] != VERSION {
+            return Err("Invalid version".to_string())
+        }
+        let table_type = TableType::from_u8(data[1]);
+        let compressed = match table_type {
+            Some(TableType::Uncompressed) => false,
+            Some(TableType::Compressed) => true,
+            _ => return Err(format!("Unexpected table type {}", data[1])),
+        };
+        let data = &data[2..];
+        let expected_len = if compressed { C_SIZE.div_ceil(2) } else { C_SIZE };
+        if data.len() != expected_len {
+            return Err(format!("Expected {expected_len} entries but got {}", data.len()));
+        }
+
+        Ok(LookupTable {
+            entries: data.to_vec().into_boxed_slice().try_into().unwrap(),
+            coord_type: PhantomData,
+            compressed
+        })
+    }
+
     pub fn get(&self, id: C) -> u8 {
         let id: usize = id.into();
         if self.compressed {
@@ -112,6 +194,97 @@ impl<const C_SIZE: usize, C: Coord<C_SIZE>> LookupTable<C_SIZE, C> {
     }
 }
 
+impl <const C_SIZE: usize, C: Coord<C_SIZE>> Into<Vec<u8>> for &SymLookupTable<C_SIZE, C> {
+    fn into(self) -> Vec<u8> {
+        self.get_bytes()
+    }
+}
+
+impl<const C_SIZE: usize, C: Coord<C_SIZE>> SymLookupTable<C_SIZE, C> {
+    fn from_parts(representatives: Box<[u64]>, entries: Box<[u8]>) -> Self {
+        let mut ranks = Vec::with_capacity(representatives.len().div_ceil(SYM_RANK_BLOCK));
+        let mut rank = 0;
+        for block in representatives.chunks(SYM_RANK_BLOCK) {
+            ranks.push(rank);
+            rank += block.iter().map(|w| w.count_ones()).sum::<u32>();
+        }
+        SymLookupTable {
+            representatives,
+            ranks: ranks.into_boxed_slice(),
+            entries,
+            coord_type: PhantomData,
+            niss: false,
+        }
+    }
+
+    //Turns a table of distances into one that also stores NISS values. They start out unknown, see set_niss
+    pub fn into_niss(mut self) -> Self {
+        for entry in self.entries.iter_mut() {
+            *entry = (*entry & 0x0F) | 0xF0;
+        }
+        self.niss = true;
+        self
+    }
+
+    pub fn class_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get_bytes(&self) -> Vec<u8> {
+        let table_type = if self.niss {
+            TableType::SymmetryNiss
+        } else {
+            TableType::Symmetry
+        };
+        let mut ser = vec![VERSION, table_type.to_u8().unwrap()];
+        for word in self.representatives.iter() {
+            ser.extend(word.to_le_bytes());
+        }
+        ser.extend(self.entries.iter());
+        ser
+    }
+
+    fn rank(&self, representative: C) -> Option<usize> {
+        let id: usize = representative.into();
+        let word = id >> 6;
+        let block_start = word - word % SYM_RANK_BLOCK;
+        if self.representatives[word] & (1 << (id & 63)) == 0 {
+            return None;
+        }
+        let mut rank = self.ranks[word / SYM_RANK_BLOCK] as usize;
+        for w in self.representatives[block_start..word].iter() {
+            rank += w.count_ones() as usize;
+        }
+        rank += (self.representatives[word] & ((1 << (id & 63)) - 1)).count_ones() as usize;
+        Some(rank)
+    }
+
+    //Expects the representative of the symmetry class, see SymCoord. Returns None for any other coordinate
+    pub fn get(&self, representative: C) -> Option<u8> {
+        let entry = self.entries[self.rank(representative)?];
+        if self.niss {
+            Some(entry & 0x0F)
+        } else {
+            Some(entry)
+        }
+    }
+
+    pub fn get_niss(&self, representative: C) -> Option<(u8, u8)> {
+        let entry = self.entries[self.rank(representative)?];
+        Some((entry & 0x0F, entry >> 4))
+    }
+
+    pub fn set_niss(&mut self, representative: C, niss: u8) -> Result<(), String> {
+        if !self.niss {
+            return Err("Table doesn't store NISS values".to_string());
+        }
+        let rank = self.rank(representative)
+            .ok_or_else(|| format!("{} is not a representative", representative.val()))?;
+        self.entries[rank] = (self.entries[rank] & 0x0F) | (niss << 4);
+        Ok(())
+    }
+}
+
 impl<const C_SIZE: usize, C: Coord<C_SIZE>> NissLookupTable<C_SIZE, C> {
     pub fn new() -> Self {
         NissLookupTable {
@@ -147,6 +320,67 @@ impl<const C_SIZE: usize, C: Coord<C_SIZE>> NissLookupTable<C_SIZE, C> {
     }
 }
 
+pub struct TableStats {
+    pub version: u8,
+    pub table_type: TableType,
+    //Number of entries per stored value. For pruning tables the value is the distance to the goal
+    pub distribution: BTreeMap<u8, usize>,
+    pub unreached: usize,
+}
+
+//Reads the header and entries of a serialized table without knowing its coordinate type
+pub fn table_stats(data: &[u8]) -> Result<TableStats, String> {
+    if data.len() < 2 {
+        return Err("Table is too short".to_string());
+    }
+    let version = data[0];
+    let table_type = TableType::from_u8(data[1]).ok_or_else(|| format!("Unknown table type {}", data[1]))?;
+    let data = &data[2..];
+    let (values, empty): (Vec<u8>, u8) = match table_type {
+        TableType::Uncompressed => (data.to_vec(), 0xFF),
+        TableType::Compressed => (data.iter().flat_map(|b| [b & 0x0F, b >> 4]).collect(), 0x0F),
+        TableType::Niss => (data.iter().map(|b| b & 0x0F).collect(), 0x0F),
+        TableType::Symmetry | TableType::SymmetryNiss => {
+            //The bitmap length isn't stored, but it's the only split where the entry count matches the set bits
+            let mut words = 0;
+            let mut classes = 0;
+            while words * 8 + classes < data.len() {
+                let word = data.get(words * 8..words * 8 + 8)
+                    .ok_or_else(|| "Truncated symmetry bitmap".to_string())?;
+                classes += u64::from_le_bytes(word.try_into().unwrap()).count_ones() as usize;
+                words += 1;
+            }
+            if words * 8 + classes != data.len() {
+                return Err("Symmetry bitmap does not match the number of entries".to_string());
+            }
+            if table_type == TableType::SymmetryNiss {
+                (data[words * 8..].iter().map(|b| b & 0x0F).collect(), 0x0F)
+            } else {
+                (data[words * 8..].to_vec(), 0xFF)
+            }
+        }
+    };
+    let mut distribution = BTreeMap::new();
+    let mut unreached = 0;
+    for v in values {
+        if v == empty {
+            unreached += 1;
+        } else {
+            *distribution.entry(v).or_insert(0) += 1;
+        }
+    }
+    Ok(TableStats {
+        version,
+        table_type,
+        distribution,
+        unreached
+    })
+}
+
+pub fn is_current_version(data: &[u8]) -> bool {
+    data.first() == Some(&VERSION)
+}
+
 pub trait EmptyVal {
     fn empty_val(&self) -> u8;
 }
@@ -167,9 +401,29 @@ impl <const C_SIZE: usize, C: Coord<C_SIZE>> EmptyVal for NissLookupTable<C_SIZE
     }
 }
 
+#[cfg(feature = "fs")]
+pub fn table_dir(puzzle_id: &str) -> PathBuf {
+    let mut dir = home_dir().unwrap();
+    dir.push(".cubelib");
+    dir.push("tables");
+    dir.push(puzzle_id);
+    dir
+}
+
+#[cfg(feature = "fs")]
+pub fn table_path(puzzle_id: &str, table_type: &str) -> PathBuf {
+    let mut dir = table_dir(puzzle_id);
+    dir.push(format!("{table_type}.tbl"));
+    dir
+}
+
 #[cfg(feature = "fs")]
 pub trait SaveToDisk {
-    fn save_to_disk(&self, puzzle_id: &str, table_type: &str) -> std::io::Result<()>;
+    fn save_to_disk(&self, puzzle_id: &str, table_type: &str) -> std::io::Result<()> {
+        self.save_to_path(&table_path(puzzle_id, table_type))
+    }
+
+    fn save_to_path(&self, path: &Path) -> std::io::Result<()>;
 }
 
 #[cfg(feature = "fs")]
@@ -177,13 +431,12 @@ pub trait LoadFromDisk {
     fn load(data: Box<Vec<u8>>) -> Result<Self, String> where Self: Sized;
 
     fn load_from_disk(puzzle_id: &str, table_type: &str) -> Result<Self, String> where Self: Sized {
-        let mut dir = home_dir().unwrap();
-        dir.push(".cubelib");
-        dir.push("tables");
-        dir.push(puzzle_id);
-        dir.push(format!("{table_type}.tbl"));
-        debug!("Loading {puzzle_id} {table_type} table from {dir:?}");
-        let mut file = File::open(dir).map_err(|e|e.to_string())?;
+        Self::load_from_path(&table_path(puzzle_id, table_type))
+    }
+
+    fn load_from_path(path: &Path) -> Result<Self, String> where Self: Sized {
+        debug!("Loading table from {path:?}");
+        let mut file = File::open(path).map_err(|e|e.to_string())?;
         let mut buffer = Box::new(Vec::new());
         file.read_to_end(&mut buffer).map_err(|e|e.to_string())?;
         Self::load(buffer)
@@ -192,57 +445,69 @@ pub trait LoadFromDisk {
 
 #[cfg(feature = "fs")]
 impl <const C_SIZE: usize, C: Coord<C_SIZE>> LoadFromDisk for LookupTable<C_SIZE, C> {
+    fn load(data: Box<Vec<u8>>) -> Result<Self, String> {
+        Self::from_bytes(data.as_slice())
+    }
+}
+
+#[cfg(feature = "fs")]
+impl <const C_SIZE: usize, C: Coord<C_SIZE>> LoadFromDisk for NissLookupTable<C_SIZE, C> {
     fn load(mut data: Box<Vec<u8>>) -> Result<Self, String> {
         let version = data[0];
         if version != VERSION {
             return Err("Invalid version".to_string())
         }
         let table_type: TableType = TableType::from_u8(data[1]).unwrap();
-        assert_ne!(table_type, TableType::Niss);
+        assert_eq!(table_type, TableType::Niss);
         data.drain(0..2);
-        if table_type == TableType::Compressed {
-            assert_eq!(data.len(), (C_SIZE + 1) / 2);
-        } else {
-            assert_eq!(data.len(), C_SIZE);
-        }
+        assert_eq!(data.len(), C_SIZE);
 
-        Ok(LookupTable {
+        Ok(NissLookupTable {
             entries: data.into_boxed_slice().try_into().unwrap(),
             coord_type: PhantomData,
-            compressed: table_type == TableType::Compressed
         })
     }
 }
 
 #[cfg(feature = "fs")]
-impl <const C_SIZE: usize, C: Coord<C_SIZE>> LoadFromDisk for NissLookupTable<C_SIZE, C> {
+impl <const C_SIZE: usize, C: Coord<C_SIZE>> LoadFromDisk for SymLookupTable<C_SIZE, C> {
     fn load(mut data: Box<Vec<u8>>) -> Result<Self, String> {
-        let version = data[0];
-        if version != VERSION {
+        if data.len() < 2 || data[0] != VERSION {
             return Err("Invalid version".to_string())
         }
-        let table_type: TableType = TableType::from_u8(data[1]).unwrap();
-        assert_eq!(table_type, TableType::Niss);
+        let niss = match TableType::from_u8(data[1]) {
+            Some(TableType::Symmetry) => false,
+            Some(TableType::SymmetryNiss) => true,
+            _ => return Err(format!("Unexpected table type {}", data[1])),
+        };
         data.drain(0..2);
-        assert_eq!(data.len(), C_SIZE);
+        let words = C_SIZE.div_ceil(64);
+        if data.len() < words * 8 {
+            return Err("Truncated symmetry bitmap".to_string());
+        }
+        let representatives: Vec<u64> = data.drain(0..words * 8)
+            .collect::<Vec<u8>>()
+            .chunks(8)
+            .map(|w| u64::from_le_bytes(w.try_into().unwrap()))
+            .collect();
+        let classes: u32 = representatives.iter().map(|w| w.count_ones()).sum();
+        if data.len() != classes as usize {
+            return Err(format!("Expected {classes} entries but got {}", data.len()));
+        }
 
-        Ok(NissLookupTable {
-            entries: data.into_boxed_slice().try_into().unwrap(),
-            coord_type: PhantomData,
-        })
+        let mut table = SymLookupTable::from_parts(representatives.into_boxed_slice(), data.into_boxed_slice());
+        table.niss = niss;
+        Ok(table)
     }
 }
 
 #[cfg(feature = "fs")]
 impl <T> SaveToDisk for T where for<'a> &'a T: Into<Vec<u8>> {
-    fn save_to_disk(&self, puzzle_id: &str, table_type: &str) -> std::io::Result<()> {
-        let mut dir = home_dir().unwrap();
-        dir.push(".cubelib");
-        dir.push("tables");
-        dir.push(puzzle_id);
-        fs::create_dir_all(dir.clone())?;
-        dir.push(format!("{table_type}.tbl"));
-        let mut file = File::create(dir)?;
+    fn save_to_path(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(path)?;
         file.write_all(Into::<Vec<u8>>::into(self).as_slice())?;
         Ok(())
     }
@@ -251,15 +516,15 @@ impl <T> SaveToDisk for T where for<'a> &'a T: Into<Vec<u8>> {
 pub fn generate<
     const COORD_SIZE: usize,
     Mapper,
-    Table: EmptyVal,
+    Table: EmptyVal + Sync,
     Init,
     Getter,
     Setter,
-    Turn: PuzzleMove + Transformable<Transformation>,
+    Turn: PuzzleMove + Transformable<Transformation> + Sync,
     Transformation: PuzzleMove,
-    PuzzleParam: Puzzle<Turn, Transformation>,
-    TransTable: TransitionTable<Turn>,
-    CoordParam: Coord<COORD_SIZE> + Copy + Hash + Eq + Debug,
+    PuzzleParam: Puzzle<Turn, Transformation> + Send + Sync,
+    TransTable: TransitionTable<Turn> + Sync,
+    CoordParam: Coord<COORD_SIZE> + Copy + Hash + Eq + Debug + Send,
 >(
     move_set: &MoveSet<Turn, TransTable>,
     mapper: &Mapper,
@@ -268,10 +533,10 @@ pub fn generate<
     setter: &Setter,
 ) -> Table
 where
-    Mapper: Fn(&PuzzleParam) -> CoordParam,
+    Mapper: Fn(&PuzzleParam) -> CoordParam + Sync,
     Init: Fn() -> Table,
     Setter: Fn(&mut Table, CoordParam, u8),
-    Getter: Fn(&Table, CoordParam) -> u8
+    Getter: Fn(&Table, CoordParam) -> u8 + Sync
 {
     let start = PuzzleParam::default();
     let mut visited = HashMap::new();
@@ -281,11 +546,16 @@ where
         to_check = pre_gen_coset_0(&move_set, mapper, &mut visited, &to_check);
     }
 
-    let mut to_check = HashMap::new();
+    // Some coordinates don't fully determine the cube states they stand for, so the cube picked as
+    // representative affects which entries are reached first. Keep the order stable so that the generated
+    // table doesn't depend on hash map iteration order or the number of threads.
+    let mut visited: Vec<(CoordParam, PuzzleParam)> = visited.into_iter().collect();
+    visited.sort_by_key(|(coord, _)| Into::<usize>::into(*coord));
     let mut table = init();
+    let mut to_check = Vec::with_capacity(visited.len());
     for (start_coord, start_cube) in visited {
         setter(&mut table, start_coord, 0);
-        to_check.insert(start_coord, start_cube);
+        to_check.push(start_cube);
     }
     if to_check.len() > 1 {
         debug!("Found {} variations of the goal state", to_check.len());
@@ -315,6 +585,132 @@ where
     table
 }
 
+//Searches over symmetry classes instead of coordinates, so the frontier only holds one state per class and the full table
+//is never built. Every class is expanded from an arbitrary member, which requires the move set to be closed under the symmetries.
+pub fn generate_sym<
+    const COORD_SIZE: usize,
+    Mapper,
+    Turn: PuzzleMove + Transformable<Transformation> + Sync,
+    Transformation: PuzzleMove,
+    PuzzleParam: Puzzle<Turn, Transformation> + Send + Sync,
+    TransTable: TransitionTable<Turn> + Sync,
+    CoordParam: SymCoord<COORD_SIZE, PuzzleParam> + Copy + Hash + Eq + Debug + Send + Sync,
+>(
+    move_set: &MoveSet<Turn, TransTable>,
+    mapper: &Mapper,
+) -> SymLookupTable<COORD_SIZE, CoordParam>
+where
+    Mapper: Fn(&PuzzleParam) -> CoordParam + Sync,
+{
+    let start = PuzzleParam::default();
+    let mut goal = HashMap::new();
+    let mut to_check = vec![start.clone()];
+    goal.insert(mapper(&start), start);
+    while !to_check.is_empty() {
+        to_check = pre_gen_coset_0(&move_set, mapper, &mut goal, &to_check);
+    }
+
+    let words = COORD_SIZE.div_ceil(64);
+    let mut representatives = vec![0u64; words];
+    let mark = |bits: &mut Vec<u64>, coord: CoordParam| {
+        let id: usize = coord.into();
+        bits[id >> 6] |= 1 << (id & 63);
+    };
+    let is_marked = |bits: &Vec<u64>, coord: CoordParam| {
+        let id: usize = coord.into();
+        bits[id >> 6] & (1 << (id & 63)) != 0
+    };
+
+    let mut goal: Vec<(CoordParam, PuzzleParam)> = goal.into_iter().collect();
+    goal.sort_by_key(|(coord, _)| Into::<usize>::into(*coord));
+    let mut depths: Vec<(CoordParam, u8)> = vec![];
+    let mut to_check = vec![];
+    for (_, cube) in goal {
+        let representative = CoordParam::representative(&cube);
+        if !is_marked(&representatives, representative) {
+            mark(&mut representatives, representative);
+            depths.push((representative, 0));
+            to_check.push(cube);
+        }
+    }
+    let mut depth = 0;
+    while !to_check.is_empty() {
+        depth += 1;
+        debug!("Found {} symmetry classes at depth {}", to_check.len(), depth - 1);
+        let shared_representatives = &representatives;
+        let candidates = par_chunks(&to_check, |chunk| {
+            let mut seen: HashSet<CoordParam> = HashSet::new();
+            let mut found: Vec<(CoordParam, PuzzleParam)> = vec![];
+            for cube in chunk {
+                for m in move_set.aux_moves.iter().chain(move_set.st_moves.iter()) {
+                    let mut cube = *cube;
+                    cube.turn(*m);
+                    let representative = CoordParam::representative(&cube);
+                    if !is_marked(shared_representatives, representative) && seen.insert(representative) {
+                        found.push((representative, cube));
+                    }
+                }
+            }
+            found
+        });
+        let mut check_next = vec![];
+        for (representative, cube) in candidates.into_iter().flat_map(|c| c.into_iter()) {
+            if is_marked(&representatives, representative) {
+                continue;
+            }
+            mark(&mut representatives, representative);
+            depths.push((representative, depth));
+            check_next.push(cube);
+        }
+        to_check = check_next;
+    }
+
+    depths.sort_unstable_by_key(|(coord, _)| Into::<usize>::into(*coord));
+    let entries: Vec<u8> = depths.into_iter().map(|(_, depth)| depth).collect();
+    debug!("Reduced {} entries to {} symmetry classes", COORD_SIZE, entries.len());
+    SymLookupTable::from_parts(representatives.into_boxed_slice(), entries.into_boxed_slice())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static GENERATION_THREADS: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+// Generates tables on the current thread with at most `threads` threads instead of one per core
+#[cfg(not(target_arch = "wasm32"))]
+pub fn with_generation_threads<R, F: FnOnce() -> R>(threads: usize, f: F) -> R {
+    let previous = GENERATION_THREADS.with(|t| t.replace(Some(threads.max(1))));
+    let result = f();
+    GENERATION_THREADS.with(|t| t.set(previous));
+    result
+}
+
+// Splits `items` into one chunk per available core and runs `f` on each chunk on its own thread.
+// The results are returned in chunk order, so callers that merge them sequentially stay deterministic.
+pub(crate) fn par_chunks<T: Sync, R: Send, F: Fn(&[T]) -> R + Sync>(items: &[T], f: F) -> Vec<R> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let threads = GENERATION_THREADS.with(|t| t.get())
+            .or_else(|| std::thread::available_parallelism().map(|n| n.get()).ok())
+            .unwrap_or(1);
+        // Spawning threads isn't worth it for the small frontiers at the start and end of a BFS
+        if threads > 1 && items.len() >= 1024 {
+            let chunk_size = items.len().div_ceil(threads);
+            return std::thread::scope(|s| {
+                let handles: Vec<_> = items
+                    .chunks(chunk_size)
+                    .map(|chunk| s.spawn(|| f(chunk)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().unwrap())
+                    .collect()
+            });
+        }
+    }
+    vec![f(items)]
+}
+
 fn pre_gen_coset_0<
     const COORD_SIZE: usize,
     Mapper,
@@ -361,14 +757,14 @@ where
 fn fill_table<
     const COORD_SIZE: usize,
     Mapper,
-    Table: EmptyVal,
+    Table: EmptyVal + Sync,
     Getter,
     Setter,
-    Turn: PuzzleMove + Transformable<Transformation>,
+    Turn: PuzzleMove + Transformable<Transformation> + Sync,
     Transformation: PuzzleMove,
-    PuzzleParam: Puzzle<Turn, Transformation>,
-    TransTable: TransitionTable<Turn>,
-    CoordParam: Coord<COORD_SIZE> + Copy + Hash + Eq + Debug,
+    PuzzleParam: Puzzle<Turn, Transformation> + Send + Sync,
+    TransTable: TransitionTable<Turn> + Sync,
+    CoordParam: Coord<COORD_SIZE> + Copy + Hash + Eq + Debug + Send,
 >(
     move_set: &MoveSet<Turn, TransTable>,
     table: &mut Table,
@@ -376,30 +772,44 @@ fn fill_table<
     mapper: &Mapper,
     getter: &Getter,
     setter: &Setter,
-    to_check: HashMap<CoordParam, PuzzleParam>,
-) -> HashMap<CoordParam, PuzzleParam>
+    to_check: Vec<PuzzleParam>,
+) -> Vec<PuzzleParam>
 where
-    Mapper: Fn(&PuzzleParam) -> CoordParam,
+    Mapper: Fn(&PuzzleParam) -> CoordParam + Sync,
     Setter: Fn(&mut Table, CoordParam, u8),
-    Getter: Fn(&Table, CoordParam) -> u8
+    Getter: Fn(&Table, CoordParam) -> u8 + Sync
 {
-    let mut next_cubes: HashMap<CoordParam, PuzzleParam> = HashMap::new();
-    for (_coord, cube) in to_check.into_iter() {
-        for m in move_set
-            .aux_moves
-            .into_iter()
-            .chain(move_set.st_moves.into_iter())
-            .cloned()
-        {
-            let mut cube = cube.clone();
-            cube.turn(m);
-            let coord = mapper(&cube);
-            let stored = getter(&table, coord);
-            if stored == table.empty_val() {
-                setter(table, coord, depth + 1);
-                next_cubes.insert(coord, cube);
+    // Every thread expands its part of the frontier against a read-only view of the table.
+    // Entries are only written once all threads are done. The chunks are merged in order and
+    // keep the first cube found per coordinate, which gives the same frontier as a single thread.
+    let shared_table: &Table = table;
+    let candidates = par_chunks(&to_check, |chunk| {
+        let mut seen: HashSet<CoordParam> = HashSet::new();
+        let mut found: Vec<(CoordParam, PuzzleParam)> = vec![];
+        for cube in chunk {
+            for m in move_set
+                .aux_moves
+                .into_iter()
+                .chain(move_set.st_moves.into_iter())
+                .cloned()
+            {
+                let mut cube = cube.clone();
+                cube.turn(m);
+                let coord = mapper(&cube);
+                if getter(shared_table, coord) == shared_table.empty_val() && seen.insert(coord) {
+                    found.push((coord, cube));
+                }
             }
         }
+        found
+    });
+
+    let mut next_cubes = vec![];
+    for (coord, cube) in candidates.into_iter().flat_map(|c| c.into_iter()) {
+        if getter(table, coord) == table.empty_val() {
+            setter(table, coord, depth + 1);
+            next_cubes.push(cube);
+        }
     }
     next_cubes
 }
@@ -9,6 +9,7 @@ use crate::steps;
 use crate::steps::step::{DefaultStepOptions, Step};
 
 pub mod lookup_table;
+pub mod table_registry;
 pub mod stream;
 pub mod solution;
 pub mod df_search;
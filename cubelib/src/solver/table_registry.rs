@@ -0,0 +1,501 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+#[cfg(feature = "fs")]
+use std::path::PathBuf;
+
+use log::error;
+#[cfg(feature = "fs")]
+use log::info;
+
+#[cfg(feature = "fs")]
+use crate::solver::lookup_table::{LoadFromDisk, SaveToDisk, table_dir};
+
+type AnyTable = Box<dyn Any + Send + Sync>;
+type TableParser = Arc<dyn Fn(&[u8]) -> Result<AnyTable, String> + Send + Sync>;
+type TableGenerator = Arc<dyn Fn(&TableRegistry) -> Result<AnyTable, String> + Send + Sync>;
+
+//Anything that can be kept in a TableRegistry. Implemented for all tables that can be cloned and serialized (and loaded with the fs feature)
+pub trait RegistryTable: Clone + Send + Sync + 'static {
+    fn to_bytes(&self) -> Vec<u8>;
+    #[cfg(feature = "fs")]
+    fn from_bytes(data: Box<Vec<u8>>) -> Result<Self, String> where Self: Sized;
+}
+
+#[cfg(not(feature = "fs"))]
+impl <T: Clone + Send + Sync + 'static> RegistryTable for T where for<'a> &'a T: Into<Vec<u8>> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.into()
+    }
+}
+
+#[cfg(feature = "fs")]
+impl <T: LoadFromDisk + Clone + Send + Sync + 'static> RegistryTable for T where for<'a> &'a T: Into<Vec<u8>> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.into()
+    }
+
+    fn from_bytes(data: Box<Vec<u8>>) -> Result<Self, String> {
+        T::load(data)
+    }
+}
+
+struct RegisteredTable {
+    generator: TableGenerator,
+    serializer: fn(&AnyTable) -> Vec<u8>,
+    cloner: fn(&AnyTable) -> AnyTable,
+    #[cfg(feature = "fs")]
+    loader: fn(Vec<u8>) -> Result<AnyTable, String>,
+    //Embedded tables are built into the binary and never read from or written to disk
+    embedded: Option<EmbeddedTable>,
+    table: OnceLock<AnyTable>,
+    //Held while the table is loaded or generated, so that happens only once even if several threads request it
+    init: Mutex<()>,
+}
+
+#[derive(Clone)]
+struct EmbeddedTable {
+    data: &'static [u8],
+    parser: TableParser,
+}
+
+impl Clone for RegisteredTable {
+    fn clone(&self) -> Self {
+        let table = OnceLock::new();
+        if let Some(t) = self.table.get() {
+            let _ = table.set((self.cloner)(t));
+        }
+        RegisteredTable {
+            generator: self.generator.clone(),
+            serializer: self.serializer,
+            cloner: self.cloner,
+            #[cfg(feature = "fs")]
+            loader: self.loader,
+            embedded: self.embedded.clone(),
+            table,
+            init: Mutex::new(()),
+        }
+    }
+}
+
+//Pruning tables by key. Tables are loaded from disk (with the fs feature) or generated the first time they are requested.
+#[derive(Clone)]
+pub struct TableRegistry {
+    puzzle_id: String,
+    //Overrides the default table directory
+    #[cfg(feature = "fs")]
+    dir: Option<PathBuf>,
+    keys: Vec<String>,
+    tables: HashMap<String, RegisteredTable>,
+}
+
+fn serialize<T: RegistryTable>(table: &AnyTable) -> Vec<u8> {
+    table.downcast_ref::<T>()
+        .expect("Registered table has the wrong type")
+        .to_bytes()
+}
+
+fn clone_table<T: RegistryTable>(table: &AnyTable) -> AnyTable {
+    Box::new(table.downcast_ref::<T>()
+        .expect("Registered table has the wrong type")
+        .clone())
+}
+
+#[cfg(feature = "fs")]
+fn deserialize<T: RegistryTable>(data: Vec<u8>) -> Result<AnyTable, String> {
+    Ok(Box::new(T::from_bytes(Box::new(data))?))
+}
+
+impl TableRegistry {
+    pub fn new(puzzle_id: &str) -> Self {
+        TableRegistry {
+            puzzle_id: puzzle_id.to_string(),
+            #[cfg(feature = "fs")]
+            dir: None,
+            keys: vec![],
+            tables: HashMap::new(),
+        }
+    }
+
+    pub fn puzzle_id(&self) -> &str {
+        self.puzzle_id.as_str()
+    }
+
+    //Tables are read from and written to ~/.cubelib/tables/<puzzle id> unless a different directory is set here
+    #[cfg(feature = "fs")]
+    pub fn set_dir(&mut self, dir: PathBuf) {
+        self.dir = Some(dir);
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| table_dir(self.puzzle_id.as_str()))
+    }
+
+    //Registering a key again replaces the previous table
+    pub fn register<T: RegistryTable, F: Fn() -> T + Send + Sync + 'static>(&mut self, key: &str, generator: F) {
+        self.insert(key, move |_: &TableRegistry| Ok(generator()), None);
+    }
+
+    //Like register, for tables that are built from other tables. The generator gets them from the registry, so they are
+    //loaded or generated only once. If it fails, e.g. because one of those tables is missing, requesting the table fails
+    //and it is generated again on the next request
+    pub fn register_derived<T: RegistryTable, F: Fn(&TableRegistry) -> Result<T, String> + Send + Sync + 'static>(&mut self, key: &str, generator: F) {
+        self.insert(key, generator, None);
+    }
+
+    //Registers a table that is built into the binary. The generator is only used if the embedded data can't be parsed,
+    //and to check the embedded data against a freshly generated table
+    pub fn register_embedded<T, P, F>(&mut self, key: &str, data: &'static [u8], parser: P, generator: F)
+        where T: RegistryTable, P: Fn(&[u8]) -> Result<T, String> + Send + Sync + 'static, F: Fn() -> T + Send + Sync + 'static {
+        let embedded = EmbeddedTable {
+            data,
+            parser: Arc::new(move |data| parser(data).map(|table| Box::new(table) as AnyTable)),
+        };
+        self.insert(key, move |_: &TableRegistry| Ok(generator()), Some(embedded));
+    }
+
+    fn insert<T: RegistryTable, F: Fn(&TableRegistry) -> Result<T, String> + Send + Sync + 'static>(&mut self, key: &str, generator: F, embedded: Option<EmbeddedTable>) {
+        if !self.tables.contains_key(key) {
+            self.keys.push(key.to_string());
+        }
+        self.tables.insert(key.to_string(), RegisteredTable {
+            generator: Arc::new(move |registry| Ok(Box::new(generator(registry)?))),
+            serializer: serialize::<T>,
+            cloner: clone_table::<T>,
+            #[cfg(feature = "fs")]
+            loader: deserialize::<T>,
+            embedded,
+            table: OnceLock::new(),
+            init: Mutex::new(()),
+        });
+    }
+
+    pub fn keys(&self) -> Vec<&str> {
+        self.keys.iter().map(|k| k.as_str()).collect()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.tables.contains_key(key)
+    }
+
+    pub fn is_embedded(&self, key: &str) -> bool {
+        self.tables.get(key)
+            .map(|t| t.embedded.is_some())
+            .unwrap_or(false)
+    }
+
+    //The data built into the binary for this key, if it is an embedded table
+    pub fn embedded_bytes(&self, key: &str) -> Option<&'static [u8]> {
+        self.tables.get(key)?
+            .embedded.as_ref()
+            .map(|e| e.data)
+    }
+
+    pub fn is_loaded(&self, key: &str) -> bool {
+        self.tables.get(key)
+            .map(|t| t.table.get().is_some())
+            .unwrap_or(false)
+    }
+
+    //Returns the table for this key, loading or generating it if that hasn't happened yet
+    pub fn get<T: 'static>(&self, key: &str) -> Result<&T, String> {
+        self.init(key)?
+            .downcast_ref::<T>()
+            .ok_or_else(|| format!("Table {key} does not have the requested type"))
+    }
+
+    //Like get, for modifying a table in place
+    pub fn get_mut<T: 'static>(&mut self, key: &str) -> Result<&mut T, String> {
+        self.gen(key)?;
+        self.tables.get_mut(key)
+            .and_then(|entry| entry.table.get_mut())
+            .and_then(|table| table.downcast_mut::<T>())
+            .ok_or_else(|| format!("Table {key} does not have the requested type"))
+    }
+
+    //Returns the table for this key only if it has already been loaded or generated
+    pub fn get_loaded<T: 'static>(&self, key: &str) -> Option<&T> {
+        self.tables.get(key)?
+            .table.get()?
+            .downcast_ref::<T>()
+    }
+
+    pub fn gen(&self, key: &str) -> Result<(), String> {
+        self.init(key)?;
+        Ok(())
+    }
+
+    pub fn get_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        let entry = self.tables.get(key)?;
+        entry.table.get().map(|t| (entry.serializer)(t))
+    }
+
+    //Runs the generator for this key without touching the stored table
+    pub fn generate_bytes(&self, key: &str) -> Result<Vec<u8>, String> {
+        let entry = self.entry(key)?;
+        Ok((entry.serializer)(&(entry.generator)(self)?))
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn load_bytes(&self, key: &str, data: Vec<u8>) -> Result<(), String> {
+        let entry = self.entry(key)?;
+        let table = (entry.loader)(data)?;
+        entry.table.set(table).map_err(|_| format!("Table {key} is already loaded"))
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn load(&self, key: &str) -> Result<(), String> {
+        let entry = self.entry(key)?;
+        if entry.table.get().is_some() {
+            return Ok(());
+        }
+        let table = self.read(key, entry)?;
+        let _ = entry.table.set(table);
+        Ok(())
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn save(&self, key: &str) -> std::io::Result<()> {
+        if let Some(data) = self.get_bytes(key) {
+            RawTable(data).save_to_path(&self.path(key))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn path(&self, key: &str) -> PathBuf {
+        self.dir().join(format!("{key}.tbl"))
+    }
+
+    //Reads the table file for this key as it is stored on disk
+    #[cfg(feature = "fs")]
+    pub fn read_bytes(&self, key: &str) -> Result<Vec<u8>, String> {
+        RawTable::load_from_path(&self.path(key)).map(|RawTable(data)| data)
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn write_bytes(&self, key: &str, data: Vec<u8>) -> std::io::Result<()> {
+        RawTable(data).save_to_path(&self.path(key))
+    }
+
+    fn entry(&self, key: &str) -> Result<&RegisteredTable, String> {
+        self.tables.get(key).ok_or_else(|| format!("Unknown table {key}"))
+    }
+
+    fn init(&self, key: &str) -> Result<&AnyTable, String> {
+        let entry = self.entry(key)?;
+        if let Some(table) = entry.table.get() {
+            return Ok(table);
+        }
+        let _guard = entry.init.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(table) = entry.table.get() {
+            return Ok(table);
+        }
+        let table = self.load_or_generate(key, entry)?;
+        Ok(entry.table.get_or_init(|| table))
+    }
+
+    #[cfg(feature = "fs")]
+    fn read(&self, key: &str, entry: &RegisteredTable) -> Result<AnyTable, String> {
+        let RawTable(data) = RawTable::load_from_path(&self.path(key))?;
+        (entry.loader)(data)
+    }
+
+    //Falls back to generating the table if the embedded data is invalid, e.g. after the table format changed
+    fn load_embedded(&self, key: &str, embedded: &EmbeddedTable, entry: &RegisteredTable) -> Result<AnyTable, String> {
+        (embedded.parser)(embedded.data).or_else(|e| {
+            error!("Invalid embedded {key} table: {e}");
+            (entry.generator)(self)
+        })
+    }
+
+    #[cfg(feature = "fs")]
+    fn load_or_generate(&self, key: &str, entry: &RegisteredTable) -> Result<AnyTable, String> {
+        if let Some(embedded) = &entry.embedded {
+            return self.load_embedded(key, embedded, entry);
+        }
+        match self.read(key, entry) {
+            Ok(table) => {
+                info!("Loaded {key} table from disk");
+                return Ok(table);
+            },
+            Err(e) => {
+                error!("Error loading {key} table from disk: {e}");
+            }
+        }
+        let table = (entry.generator)(self)?;
+        if let Err(e) = RawTable((entry.serializer)(&table)).save_to_path(&self.path(key)) {
+            error!("Error saving {key} table to disk: {e}");
+        } else {
+            info!("Saved {key} table to disk");
+        }
+        Ok(table)
+    }
+
+    #[cfg(not(feature = "fs"))]
+    fn load_or_generate(&self, key: &str, entry: &RegisteredTable) -> Result<AnyTable, String> {
+        match &entry.embedded {
+            Some(embedded) => self.load_embedded(key, embedded, entry),
+            None => (entry.generator)(self),
+        }
+    }
+}
+
+//The serialized form of a table, so the registry can read and write files without knowing the table type
+#[cfg(feature = "fs")]
+struct RawTable(Vec<u8>);
+
+#[cfg(feature = "fs")]
+impl From<&RawTable> for Vec<u8> {
+    fn from(value: &RawTable) -> Self {
+        value.0.clone()
+    }
+}
+
+#[cfg(feature = "fs")]
+impl LoadFromDisk for RawTable {
+    fn load(data: Box<Vec<u8>>) -> Result<Self, String> {
+        Ok(RawTable(*data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct TestTable(Vec<u8>);
+
+    impl From<&TestTable> for Vec<u8> {
+        fn from(value: &TestTable) -> Self {
+            value.0.clone()
+        }
+    }
+
+    #[cfg(feature = "fs")]
+    impl LoadFromDisk for TestTable {
+        fn load(data: Box<Vec<u8>>) -> Result<Self, String> {
+            Ok(TestTable(*data))
+        }
+    }
+
+    //Keeps generated tables out of ~/.cubelib
+    fn registry(name: &str) -> TableRegistry {
+        #[allow(unused_mut)]
+        let mut registry = TableRegistry::new("test");
+        #[cfg(feature = "fs")]
+        {
+            let dir = std::env::temp_dir().join(format!("cubelib-registry-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            registry.set_dir(dir);
+        }
+        #[cfg(not(feature = "fs"))]
+        let _ = name;
+        registry
+    }
+
+    #[test]
+    fn get_generates_a_table_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut registry = registry("once");
+        let counter = calls.clone();
+        registry.register("a", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            TestTable(vec![1, 2, 3])
+        });
+        assert!(!registry.is_loaded("a"));
+        assert_eq!(registry.get_loaded::<TestTable>("a"), None);
+        assert_eq!(registry.get::<TestTable>("a"), Ok(&TestTable(vec![1, 2, 3])));
+        assert_eq!(registry.get::<TestTable>("a"), Ok(&TestTable(vec![1, 2, 3])));
+        assert!(registry.is_loaded("a"));
+        assert_eq!(registry.get_bytes("a"), Some(vec![1, 2, 3]));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn get_rejects_unknown_keys_and_wrong_types() {
+        let mut registry = registry("errors");
+        registry.register("a", || TestTable(vec![1]));
+        assert_eq!(registry.get::<TestTable>("b"), Err("Unknown table b".to_string()));
+        assert_eq!(registry.get::<Vec<u8>>("a"), Err("Table a does not have the requested type".to_string()));
+        assert_eq!(registry.gen("b"), Err("Unknown table b".to_string()));
+    }
+
+    #[test]
+    fn gen_loads_the_table_without_returning_it() {
+        let mut registry = registry("gen");
+        registry.register("a", || TestTable(vec![4]));
+        registry.gen("a").unwrap();
+        assert_eq!(registry.get_loaded::<TestTable>("a"), Some(&TestTable(vec![4])));
+    }
+
+    #[test]
+    fn registering_a_key_again_replaces_the_table() {
+        let mut registry = registry("replace");
+        registry.register("a", || TestTable(vec![1]));
+        registry.register("b", || TestTable(vec![2]));
+        registry.register("a", || TestTable(vec![3]));
+        assert_eq!(registry.keys(), vec!["a", "b"]);
+        assert_eq!(registry.get::<TestTable>("a"), Ok(&TestTable(vec![3])));
+    }
+
+    #[test]
+    fn generate_bytes_does_not_store_the_table() {
+        let mut registry = registry("bytes");
+        registry.register("a", || TestTable(vec![5, 6]));
+        assert_eq!(registry.generate_bytes("a"), Ok(vec![5, 6]));
+        assert!(!registry.is_loaded("a"));
+    }
+
+    #[test]
+    fn derived_tables_get_their_inputs_from_the_registry() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut registry = registry("derived");
+        let counter = calls.clone();
+        registry.register("base", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            TestTable(vec![1, 2])
+        });
+        registry.register_derived("derived", |registry: &TableRegistry| {
+            let base = registry.get::<TestTable>("base")?;
+            Ok(TestTable(base.0.iter().map(|v| v * 10).collect()))
+        });
+        assert_eq!(registry.get::<TestTable>("derived"), Ok(&TestTable(vec![10, 20])));
+        assert_eq!(registry.get::<TestTable>("base"), Ok(&TestTable(vec![1, 2])));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn failing_derived_tables_are_not_stored() {
+        let mut registry = registry("failing");
+        registry.register_derived("derived", |registry: &TableRegistry| {
+            let base = registry.get::<TestTable>("base")?;
+            Ok(base.clone())
+        });
+        assert_eq!(registry.get::<TestTable>("derived"), Err("Unknown table base".to_string()));
+        assert_eq!(registry.generate_bytes("derived"), Err("Unknown table base".to_string()));
+        assert!(!registry.is_loaded("derived"));
+
+        registry.register("base", || TestTable(vec![7]));
+        assert_eq!(registry.get::<TestTable>("derived"), Ok(&TestTable(vec![7])));
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn generated_tables_are_saved_and_loaded_again() {
+        let mut registry = registry("disk");
+        registry.register("a", || TestTable(vec![8, 9]));
+        registry.gen("a").unwrap();
+        assert_eq!(registry.read_bytes("a"), Ok(vec![8, 9]));
+
+        let mut reloaded = TableRegistry::new("test");
+        reloaded.set_dir(registry.dir());
+        reloaded.register("a", || -> TestTable { panic!("The table should be loaded from disk") });
+        assert_eq!(reloaded.get::<TestTable>("a"), Ok(&TestTable(vec![8, 9])));
+        let _ = std::fs::remove_dir_all(registry.dir());
+    }
+}
@@ -1,4 +1,5 @@
 use std::hash::Hash;
+use crate::puzzles::puzzle::{PuzzleMove, TransformableMut};
 
 pub trait Coord<const SIZE: usize>: Into<usize> + Copy + Clone + Eq + PartialEq + Hash {
     fn size() -> usize {
@@ -28,3 +29,45 @@ impl <P> From<&P> for ZeroCoord {
     }
 }
 
+
+//A coordinate that maps all states which are conjugates of each other under a group of symmetries to the same pruning value.
+//The smallest coordinate of such a symmetry class is used as its representative.
+pub trait SymCoord<const SIZE: usize, PuzzleParam>: Coord<SIZE> {
+    fn representative(puzzle: &PuzzleParam) -> Self;
+}
+
+pub fn min_conjugate<const SIZE: usize, C, Transformation, PuzzleParam>(puzzle: &PuzzleParam, symmetries: &[&[Transformation]]) -> C
+    where
+        C: Coord<SIZE> + for<'x> From<&'x PuzzleParam>,
+        Transformation: PuzzleMove,
+        PuzzleParam: TransformableMut<Transformation> + Copy {
+    let mut min = C::from(puzzle);
+    for symmetry in symmetries.iter().filter(|s| !s.is_empty()) {
+        let mut conjugate = *puzzle;
+        for t in symmetry.iter() {
+            conjugate.transform(*t);
+        }
+        let coord = C::from(&conjugate);
+        if coord.val() < min.val() {
+            min = coord;
+        }
+    }
+    min
+}
+
+//Same as min_conjugate, but the symmetries are also combined with a reflection of the puzzle, which doubles the group
+pub fn min_conjugate_mirrored<const SIZE: usize, C, Transformation, PuzzleParam>(puzzle: &PuzzleParam, symmetries: &[&[Transformation]], mirror: impl Fn(&mut PuzzleParam)) -> C
+    where
+        C: Coord<SIZE> + for<'x> From<&'x PuzzleParam>,
+        Transformation: PuzzleMove,
+        PuzzleParam: TransformableMut<Transformation> + Copy {
+    let mut mirrored = *puzzle;
+    mirror(&mut mirrored);
+    let min: C = min_conjugate(puzzle, symmetries);
+    let min_mirrored: C = min_conjugate(&mirrored, symmetries);
+    if min_mirrored.val() < min.val() {
+        min_mirrored
+    } else {
+        min
+    }
+}
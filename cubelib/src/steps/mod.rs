@@ -1,2 +1,4 @@
 pub mod step;
 pub mod coord;
+pub mod params;
+pub mod pipeline;
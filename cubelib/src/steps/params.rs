@@ -0,0 +1,107 @@
+use crate::steps::step::StepConfig;
+
+//Describes a parameter that a step accepts in StepConfig.params, e.g. the triggers in `DR[triggers=R,RUR]`
+pub struct ParamSpec {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub kind: ParamKind,
+}
+
+pub enum ParamKind {
+    //A single value. The check returns a description of the valid values on failure
+    Value(fn(&str) -> Result<(), String>),
+    //Comma separated list where every entry has to pass the check
+    List(fn(&str) -> Result<(), String>),
+}
+
+impl ParamSpec {
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self.kind {
+            ParamKind::Value(check) => check(value.trim()),
+            ParamKind::List(check) => {
+                for entry in value.split(',').map(|e| e.trim()) {
+                    if entry.is_empty() {
+                        return Err(format!("Empty entry in '{value}'"));
+                    }
+                    check(entry)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+//Options that every step accepts. They are parsed into their own StepConfig fields and never end up in params
+const COMMON_OPTIONS: [&str; 6] = ["min", "max", "min-abs", "max-abs", "limit", "niss"];
+
+//Rejects params that aren't declared by the step, or whose values don't match their declaration
+pub fn validate(config: &StepConfig, specs: &[ParamSpec]) -> Result<(), String> {
+    let step_name = config.kind.to_string().to_uppercase();
+    let mut keys: Vec<&String> = config.params.keys().collect();
+    keys.sort();
+    for key in keys {
+        let spec = specs.iter()
+            .find(|s| s.key == key)
+            .ok_or_else(|| if specs.is_empty() {
+                format!("Unknown parameter '{key}' for {step_name}. {step_name} only accepts the common options: {}", COMMON_OPTIONS.join(", "))
+            } else {
+                format!("Unknown parameter '{key}' for {step_name}. Valid parameters are: {}, and the common options: {}", describe(specs), COMMON_OPTIONS.join(", "))
+            })?;
+        spec.validate(config.params[key].as_str())
+            .map_err(|e| format!("Invalid value for {step_name} parameter '{key}'. {e}"))?;
+    }
+    Ok(())
+}
+
+fn describe(specs: &[ParamSpec]) -> String {
+    specs.iter()
+        .map(|s| format!("{} ({})", s.key, s.description))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(all(test, feature = "333htr"))]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::puzzles::c333::steps::dr::dr_config;
+    use crate::puzzles::c333::steps::htr::htr_config;
+
+    fn validate_step(step: &str, specs: &[ParamSpec]) -> Result<(), String> {
+        let config = StepConfig::from_str(step).unwrap_or_else(|e| panic!("Failed to parse '{step}': {e}"));
+        validate(&config, specs)
+    }
+
+    #[test]
+    fn valid_params_pass() {
+        assert_eq!(validate_step("DR", dr_config::PARAMS), Ok(()));
+        assert_eq!(validate_step("DR[triggers=R,RUR,RU'R,RU2R]", dr_config::PARAMS), Ok(()));
+        assert_eq!(validate_step("HTR[subsets=4a1,2c3]", htr_config::PARAMS), Ok(()));
+        assert_eq!(validate_step("HTR[subsets=4]", htr_config::PARAMS), Ok(()));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let error = validate_step("DR[trigers=R]", dr_config::PARAMS).unwrap_err();
+        assert!(error.starts_with("Unknown parameter 'trigers' for DR. Valid parameters are: triggers"), "{error}");
+        let error = validate_step("HTR[subset=4a1]", htr_config::PARAMS).unwrap_err();
+        assert!(error.starts_with("Unknown parameter 'subset' for HTR. Valid parameters are: subsets"), "{error}");
+    }
+
+    #[test]
+    fn steps_without_params_list_the_common_options() {
+        let error = validate_step("EO[triggers=R]", &[]).unwrap_err();
+        assert_eq!(error, "Unknown parameter 'triggers' for EO. EO only accepts the common options: min, max, min-abs, max-abs, limit, niss");
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let error = validate_step("HTR[subsets=4a1,4x9]", htr_config::PARAMS).unwrap_err();
+        assert!(error.starts_with("Invalid value for HTR parameter 'subsets'. Unrecognized subset '4x9'"), "{error}");
+        let error = validate_step("DR[triggers=R,X]", dr_config::PARAMS).unwrap_err();
+        assert_eq!(error, "Invalid value for DR parameter 'triggers'. Unable to parse trigger 'X'");
+        let error = validate_step("DR[triggers=R,,RUR]", dr_config::PARAMS).unwrap_err();
+        assert_eq!(error, "Invalid value for DR parameter 'triggers'. Empty entry in 'R,,RUR'");
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::defs::{NissSwitchType, StepKind};
+use crate::steps::step::StepConfig;
+
+//A list of steps in the form `EO[ud;max=5] > DR[triggers=R,RU2R] > HTR > FIN`.
+//Every step can have options in square brackets, separated by `;`. Options without a `=` are variations (substeps).
+//Values that contain any of `[];=>"` or start or end with whitespace have to be quoted with double quotes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub steps: Vec<StepConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    //Position of the error in characters, starting at 0
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position + 1)
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(value: ParseError) -> Self {
+        value.to_string()
+    }
+}
+
+impl Pipeline {
+    pub fn new(steps: Vec<StepConfig>) -> Pipeline {
+        Pipeline {
+            steps
+        }
+    }
+
+    //Sets the NISS type of all steps that don't define their own
+    pub fn with_default_niss(mut self, niss: Option<NissSwitchType>) -> Self {
+        for step in self.steps.iter_mut() {
+            if step.niss.is_none() {
+                step.niss = niss;
+            }
+        }
+        self
+    }
+
+    pub fn with_quality(mut self, quality: usize) -> Self {
+        for step in self.steps.iter_mut() {
+            step.quality = quality;
+        }
+        self
+    }
+}
+
+impl From<Vec<StepConfig>> for Pipeline {
+    fn from(value: Vec<StepConfig>) -> Self {
+        Pipeline::new(value)
+    }
+}
+
+impl From<Pipeline> for Vec<StepConfig> {
+    fn from(value: Pipeline) -> Self {
+        value.steps
+    }
+}
+
+impl FromStr for Pipeline {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let mut steps = vec![parser.step()?];
+        loop {
+            parser.skip_whitespace();
+            match parser.peek() {
+                None => break,
+                Some('>') => {
+                    parser.next();
+                    steps.push(parser.step()?);
+                },
+                Some(c) => return Err(parser.error(format!("Expected '>' or end of input but found '{c}'"))),
+            }
+        }
+        Ok(Pipeline::new(steps))
+    }
+}
+
+impl FromStr for StepConfig {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let step = parser.step()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(step),
+            Some(c) => Err(parser.error(format!("Expected end of input but found '{c}'"))),
+        }
+    }
+}
+
+impl Display for Pipeline {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            if i > 0 {
+                write!(f, " > ")?;
+            }
+            write!(f, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+//Prints the step in the canonical form used by Pipeline. Quality is a global setting and not part of it
+impl Display for StepConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match &self.kind {
+            StepKind::EO => "EO",
+            StepKind::RZP => "RZP",
+            StepKind::DR => "DR",
+            StepKind::HTR => "HTR",
+            StepKind::FR => "FR",
+            StepKind::FRLS => "FRLS",
+            StepKind::FIN => "FIN",
+            StepKind::Other(name) => name.as_str(),
+        };
+        write!(f, "{name}")?;
+
+        let mut options: Vec<String> = self.substeps.iter()
+            .flat_map(|s| s.iter())
+            .map(|s| quote(s))
+            .collect();
+        let mut option = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                options.push(format!("{key}={value}"));
+            }
+        };
+        option("min", self.min.map(|v| v.to_string()));
+        option("max", self.max.map(|v| v.to_string()));
+        option("min-abs", self.absolute_min.map(|v| v.to_string()));
+        option("max-abs", self.absolute_max.map(|v| v.to_string()));
+        option("limit", self.step_limit.map(|v| v.to_string()));
+        option("niss", self.niss.map(|v| match v {
+            NissSwitchType::Never => "none",
+            NissSwitchType::Before => "before",
+            NissSwitchType::Always => "always",
+        }.to_string()));
+        let mut params: Vec<(&String, &String)> = self.params.iter().collect();
+        params.sort();
+        for (key, value) in params {
+            options.push(format!("{}={}", quote(key), quote(value)));
+        }
+
+        if !options.is_empty() {
+            write!(f, "[{}]", options.join(";"))?;
+        }
+        Ok(())
+    }
+}
+
+const SPECIAL_CHARS: [char; 6] = ['[', ']', ';', '=', '>', '"'];
+
+fn quote(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.contains(SPECIAL_CHARS);
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(s: &str) -> Parser {
+        Parser {
+            chars: s.chars().collect(),
+            pos: 0
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, position: usize, message: String) -> ParseError {
+        ParseError {
+            position,
+            message
+        }
+    }
+
+    fn step(&mut self) -> Result<StepConfig, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(match self.peek() {
+                Some(c) => self.error(format!("Expected step name but found '{c}'")),
+                None => self.error("Expected step name".to_string()),
+            });
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        let mut step = StepConfig::new(StepKind::from_str(name.as_str()).unwrap());
+        self.skip_whitespace();
+        if self.peek() == Some('[') {
+            self.next();
+            self.options(&mut step)?;
+        }
+        Ok(step)
+    }
+
+    fn options(&mut self, step: &mut StepConfig) -> Result<(), ParseError> {
+        let mut seen_keys: HashSet<String> = HashSet::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(']') => {
+                    self.next();
+                    return Ok(());
+                },
+                Some(';') => {
+                    self.next();
+                    continue;
+                },
+                None => return Err(self.error("Expected ']'".to_string())),
+                _ => {}
+            }
+            let key_pos = self.pos;
+            let key = self.value()?;
+            self.skip_whitespace();
+            if self.peek() == Some('=') {
+                self.next();
+                self.skip_whitespace();
+                let value_pos = self.pos;
+                let value = self.value()?;
+                let canonical_key = match key.as_str() {
+                    "min-rel" => "min",
+                    "max-rel" => "max",
+                    k => k,
+                };
+                if !seen_keys.insert(canonical_key.to_string()) {
+                    return Err(self.error_at(key_pos, format!("Duplicate option '{key}'")));
+                }
+                Self::set_option(step, key.as_str(), value, value_pos)?;
+            } else {
+                step.substeps.get_or_insert_with(Vec::new).push(key);
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(';') => {
+                    self.next();
+                },
+                Some(']') => {},
+                Some(c) => return Err(self.error(format!("Expected ';' or ']' but found '{c}'"))),
+                None => return Err(self.error("Expected ']'".to_string())),
+            }
+        }
+    }
+
+    //Reads either a quoted string or everything up to the next special character, without surrounding whitespace
+    fn value(&mut self) -> Result<String, ParseError> {
+        if self.peek() == Some('"') {
+            let start = self.pos;
+            self.next();
+            let mut value = String::new();
+            loop {
+                match self.next() {
+                    Some('"') => return Ok(value),
+                    Some('\\') => match self.next() {
+                        Some(c) => value.push(c),
+                        None => return Err(self.error_at(start, "Unterminated quote".to_string())),
+                    },
+                    Some(c) => value.push(c),
+                    None => return Err(self.error_at(start, "Unterminated quote".to_string())),
+                }
+            }
+        }
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !SPECIAL_CHARS.contains(&c)) {
+            self.pos += 1;
+        }
+        let value: String = self.chars[start..self.pos].iter().collect();
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(match self.peek() {
+                Some(c) => self.error(format!("Expected value but found '{c}'")),
+                None => self.error("Expected value".to_string()),
+            });
+        }
+        Ok(value.to_string())
+    }
+
+    fn set_option(step: &mut StepConfig, key: &str, value: String, pos: usize) -> Result<(), ParseError> {
+        fn number<T: FromStr>(key: &str, value: &str, pos: usize) -> Result<T, ParseError> where T::Err: Display {
+            T::from_str(value).map_err(|e| ParseError {
+                position: pos,
+                message: format!("Unable to parse value '{value}' for {key}. '{e}'")
+            })
+        }
+        match key {
+            "limit" => step.step_limit = Some(number(key, value.as_str(), pos)?),
+            "min" | "min-rel" => step.min = Some(number(key, value.as_str(), pos)?),
+            "max" | "max-rel" => step.max = Some(number(key, value.as_str(), pos)?),
+            "min-abs" => step.absolute_min = Some(number(key, value.as_str(), pos)?),
+            "max-abs" => step.absolute_max = Some(number(key, value.as_str(), pos)?),
+            "niss" => step.niss = Some(match value.as_str() {
+                "always" | "true" => NissSwitchType::Always,
+                "before" => NissSwitchType::Before,
+                "none" | "never" | "false" => NissSwitchType::Never,
+                x => return Err(ParseError {
+                    position: pos,
+                    message: format!("Invalid NISS type {x}. Expected one of 'always', 'before', 'none'")
+                }),
+            }),
+            _ => {
+                step.params.insert(key.to_string(), value);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Pipeline {
+        Pipeline::from_str(s).unwrap_or_else(|e| panic!("Failed to parse '{s}': {e}"))
+    }
+
+    fn error_position(s: &str) -> usize {
+        match Pipeline::from_str(s) {
+            Ok(p) => panic!("Expected '{s}' to fail but got '{p}'"),
+            Err(e) => e.position,
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for s in [
+            "EO > RZP > DR[triggers=R,RU2R] > HTR > FIN",
+            "EO[ud;fb;min=2;max=5;niss=always] > DR[rl;max-abs=12;limit=100;niss=before]",
+            "FR[min-abs=3;niss=none;custom=1;other=\"a b\"] > FRLS > FIN",
+            "Custom_step-1[\"x;y\";key=\"[v]\"]",
+        ] {
+            let pipeline = parse(s);
+            let printed = pipeline.to_string();
+            assert_eq!(parse(printed.as_str()), pipeline, "'{s}' printed as '{printed}'");
+            assert_eq!(parse(printed.as_str()).to_string(), printed);
+        }
+    }
+
+    #[test]
+    fn display_is_canonical() {
+        let pipeline = parse("  eo [ max-rel = 5 ; ud ]>dr[niss=true;b=2;a=1]  ");
+        assert_eq!(pipeline.to_string(), "EO[ud;max=5] > DR[niss=always;a=1;b=2]");
+    }
+
+    #[test]
+    fn quoted_values() {
+        let pipeline = parse(r#"DR[triggers="R;U]";name="a\"b";"x=y"]"#);
+        let step = &pipeline.steps[0];
+        assert_eq!(step.params.get("triggers").map(String::as_str), Some("R;U]"));
+        assert_eq!(step.params.get("name").map(String::as_str), Some("a\"b"));
+        assert_eq!(step.substeps, Some(vec!["x=y".to_string()]));
+        assert_eq!(parse(pipeline.to_string().as_str()), pipeline);
+        assert_eq!(quote("a\"b"), r#""a\"b""#);
+        assert_eq!(quote(" a"), "\" a\"");
+        assert_eq!(quote(""), "\"\"");
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(error_position(""), 0);
+        assert_eq!(error_position("EO > > DR"), 5);
+        assert_eq!(error_position("EO ud"), 3);
+        assert_eq!(error_position("EO[ud"), 5);
+        assert_eq!(error_position("DR[max=x]"), 7);
+        assert_eq!(error_position("DR[niss=sometimes]"), 8);
+        assert_eq!(error_position("EO[max=3;max-rel=4]"), 9);
+        assert_eq!(error_position("DR[a=\"bc]"), 5);
+        assert_eq!(error_position("DR[a=b c=d]"), 8);
+        assert_eq!(Pipeline::from_str("EO >").unwrap_err().to_string(), "Expected step name at position 5");
+    }
+}
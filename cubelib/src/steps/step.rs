@@ -10,7 +10,7 @@ use crate::algs::Algorithm;
 use crate::defs::*;
 use crate::puzzles::puzzle::{ApplyAlgorithm, Puzzle, PuzzleMove, Transformable};
 use crate::solver::df_search::dfs_iter;
-use crate::solver::lookup_table::LookupTable;
+use crate::solver::lookup_table::{LookupTable, TableLookup};
 use crate::solver::moveset::{MoveSet, TransitionTable};
 use crate::solver::solution::{Solution, SolutionStep};
 use crate::solver::stream;
@@ -112,7 +112,8 @@ pub struct DefaultPruningTableStep<
     Turn: PuzzleMove + Transformable<Transformation>,
     Transformation: PuzzleMove,
     PuzzleParam: Puzzle<Turn, Transformation>,
-    TransTable: TransitionTable<Turn> + 'static
+    TransTable: TransitionTable<Turn> + 'static,
+    Table: TableLookup<PuzzleParam> = LookupTable<HC_SIZE, HC>,
 >
     where
         HC: for<'x> From<&'x PuzzleParam>,
@@ -120,15 +121,16 @@ pub struct DefaultPruningTableStep<
 {
     move_set: &'a MoveSet<Turn, TransTable>,
     pre_trans: Vec<Transformation>,
-    table: &'a LookupTable<HC_SIZE, HC>,
+    table: &'a Table,
     name: &'a str,
     post_step_checker: Vec<Box<dyn PostStepCheck<Turn, Transformation, PuzzleParam>>>,
+    _hc: PhantomData<HC>,
     _pc: PhantomData<PC>,
     _puzzle: PhantomData<PuzzleParam>,
     _turn: PhantomData<Turn>,
 }
 
-impl <'a, const HC_SIZE: usize, HC: Coord<HC_SIZE>, const PC_SIZE: usize, PC: Coord<PC_SIZE>, Turn: PuzzleMove + Transformable<Transformation>, Transformation: PuzzleMove, PuzzleParam: Puzzle<Turn, Transformation>, TransTable: TransitionTable<Turn>> PreStepCheck<Turn, Transformation, PuzzleParam> for DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable>
+impl <'a, const HC_SIZE: usize, HC: Coord<HC_SIZE>, const PC_SIZE: usize, PC: Coord<PC_SIZE>, Turn: PuzzleMove + Transformable<Transformation>, Transformation: PuzzleMove, PuzzleParam: Puzzle<Turn, Transformation>, TransTable: TransitionTable<Turn>, Table: TableLookup<PuzzleParam>> PreStepCheck<Turn, Transformation, PuzzleParam> for DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable, Table>
     where
         HC: for<'x> From<&'x PuzzleParam>,
         PC: for<'x> From<&'x PuzzleParam>, {
@@ -138,7 +140,7 @@ impl <'a, const HC_SIZE: usize, HC: Coord<HC_SIZE>, const PC_SIZE: usize, PC: Co
     }
 }
 
-impl <'a, const HC_SIZE: usize, HC: Coord<HC_SIZE>, const PC_SIZE: usize, PC: Coord<PC_SIZE>, Turn: PuzzleMove + Transformable<Transformation>, Transformation: PuzzleMove, PuzzleParam: Puzzle<Turn, Transformation>, TransTable: TransitionTable<Turn>> PostStepCheck<Turn, Transformation, PuzzleParam> for DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable>
+impl <'a, const HC_SIZE: usize, HC: Coord<HC_SIZE>, const PC_SIZE: usize, PC: Coord<PC_SIZE>, Turn: PuzzleMove + Transformable<Transformation>, Transformation: PuzzleMove, PuzzleParam: Puzzle<Turn, Transformation>, TransTable: TransitionTable<Turn>, Table: TableLookup<PuzzleParam>> PostStepCheck<Turn, Transformation, PuzzleParam> for DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable, Table>
     where
         HC: for<'x> From<&'x PuzzleParam>,
         PC: for<'x> From<&'x PuzzleParam>, {
@@ -158,8 +160,9 @@ impl <
     Turn: PuzzleMove + Transformable<Transformation>,
     Transformation: PuzzleMove,
     PuzzleParam: Puzzle<Turn, Transformation>,
-    TransTable: TransitionTable<Turn>>
-StepVariant<Turn, Transformation, PuzzleParam, TransTable> for DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable>
+    TransTable: TransitionTable<Turn>,
+    Table: TableLookup<PuzzleParam>>
+StepVariant<Turn, Transformation, PuzzleParam, TransTable> for DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable, Table>
 where
     HC: for<'x> From<&'x PuzzleParam>,
     PC: for<'x> From<&'x PuzzleParam>, {
@@ -174,8 +177,7 @@ where
 
     fn heuristic(&self, cube: &PuzzleParam, _: u8, can_niss: bool) -> u8 {
 
-        let coord = HC::from(cube);
-        let heuristic = self.table.get(coord);
+        let heuristic = self.table.lookup(cube);
         if can_niss {
             min(1, heuristic)
         } else {
@@ -198,15 +200,16 @@ impl <
     Transformation: PuzzleMove,
     PuzzleParam: Puzzle<Turn, Transformation>,
     TransTable: TransitionTable<Turn>,
+    Table: TableLookup<PuzzleParam>,
 >
-DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable>
+DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, PuzzleParam, TransTable, Table>
     where
         HC: for<'x> From<&'x PuzzleParam>,
         PC: for<'x> From<&'x PuzzleParam>, {
 
     pub fn new(move_set: &'a MoveSet<Turn, TransTable>,
                pre_trans: Vec<Transformation>,
-               table: &'a LookupTable<HC_SIZE, HC>,
+               table: &'a Table,
                post_step_checker: Vec<Box<dyn PostStepCheck<Turn, Transformation, PuzzleParam>>>,
                name: &'a str) -> Self {
         DefaultPruningTableStep {
@@ -216,6 +219,7 @@ DefaultPruningTableStep<'a, HC_SIZE, HC, PC_SIZE, PC, Turn, Transformation, Puzz
             name,
             post_step_checker,
             _puzzle: PhantomData::default(),
+            _hc: PhantomData::default(),
             _pc: PhantomData::default(),
             _turn: PhantomData::default(),
         }
@@ -240,6 +244,23 @@ impl<'a, Turn: PuzzleMove + Transformable<Transformation>, Transformation: Puzzl
     pub fn kind(&self) -> StepKind {
         self.kind.clone()
     }
+
+    //The heuristic of every variant that can be started from this cube, by variant name. For pruning table steps this is the exact distance
+    pub fn distances(&self, cube: &PuzzleParam) -> Vec<(String, u8)> {
+        self.step_variants.iter()
+            .filter_map(|variant| {
+                let mut cube = *cube;
+                for t in variant.pre_step_trans().iter().cloned() {
+                    cube.transform(t);
+                }
+                if variant.is_cube_ready(&cube) {
+                    Some((variant.name().to_string(), variant.heuristic(&cube, 0, false)))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 pub fn first_step<
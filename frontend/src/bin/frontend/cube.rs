@@ -2,12 +2,13 @@ use std::str::FromStr;
 
 use cubelib::algs::Algorithm;
 use cubelib::puzzles::c333::Cube333;
+use cubelib::puzzles::c333::mask::{Mask, MASK_NAMES};
 use cubelib::puzzles::cube::{CubeColor, CubeFace};
 use cubelib::puzzles::puzzle::ApplyAlgorithm;
 use leptos::*;
 use leptonic::prelude::*;
 use leptos::html::Div;
-use crate::util::RwSignalTup;
+use crate::util::{RwSignalTup, use_local_storage};
 
 #[component]
 pub fn ScrambleComponent() -> impl IntoView {
@@ -20,60 +21,76 @@ pub fn ScrambleComponent() -> impl IntoView {
                 cube
             })
     });
+    let mask_name = use_local_storage("scramble-mask", "none".to_string());
+    let mask = Signal::derive(move || Mask::from_str(mask_name.0.get().as_str()).unwrap_or(Mask::all()));
 
     view! {
         <div>
             <TextInput get=scramble.0 set=scramble.1 placeholder={"R' U' F".to_owned()}/>
+            <div style="display: flex; align-items: center; margin-top: 5px;">
+                <label style="margin-right: 10px;">"Mask:"</label>
+                <Select
+                    options=MASK_NAMES.iter().map(|m| m.to_string()).collect::<Vec<String>>()
+                    search_text_provider=move |o: String| o
+                    render_option=move |o: String| o.into_view()
+                    selected=mask_name.0
+                    set_selected=move |v| mask_name.1.set(v)
+                />
+            </div>
             <Show
                 when=move || {cube.get().is_some()}
                 fallback=|| view! {<br/><Chip color=ChipColor::Danger>"Invalid scramble"</Chip>}
             >
                 <Cube cube=Signal::derive(move ||{
                     cube.get().unwrap_or(Cube333::default())
-                })/>
+                }) mask=mask/>
             </Show>
         </div>
     }
 }
 
 #[component]
-pub fn Cube(cube: Signal<Cube333>) -> impl IntoView {
+pub fn Cube(cube: Signal<Cube333>, #[prop(into, optional)] mask: Option<Signal<Mask>>) -> impl IntoView {
     let facelets = Signal::derive(move || {
-        let facelets = cube.get().get_facelets();
+        let facelets = match mask {
+            Some(mask) => mask.get().apply(&cube.get()),
+            None => cube.get().get_facelets(),
+        };
 
-        let mut colors: Vec<CubeColor> = vec![];
-        for x in (0..3) {
-            colors.append(&mut vec![CubeColor::None; 3]);
-            for y in (0..3) {
-                colors.push(facelets[CubeFace::Up][x * 3 + y]);
+        //None marks the empty space around the net, CubeColor::None a sticker hidden by the mask
+        let mut colors: Vec<Option<CubeColor>> = vec![];
+        for x in 0..3 {
+            colors.append(&mut vec![None; 3]);
+            for y in 0..3 {
+                colors.push(Some(facelets[CubeFace::Up][x * 3 + y]));
             }
-            colors.append(&mut vec![CubeColor::None; 6]);
+            colors.append(&mut vec![None; 6]);
         }
         for x in 0..3 {
             for face in vec![CubeFace::Left, CubeFace::Front, CubeFace::Right, CubeFace::Back] {
                 for y in 0..3 {
-                    colors.push(facelets[face][x * 3 + y]);
+                    colors.push(Some(facelets[face][x * 3 + y]));
                 }
             }
         }
 
         for x in 0..3 {
-            colors.append(&mut vec![CubeColor::None; 3]);
+            colors.append(&mut vec![None; 3]);
             for y in 0..3 {
-                colors.push(facelets[CubeFace::Down][x * 3 + y]);
+                colors.push(Some(facelets[CubeFace::Down][x * 3 + y]));
             }
-            colors.append(&mut vec![CubeColor::None; 6]);
+            colors.append(&mut vec![None; 6]);
         }
         let html_facelets: Vec<HtmlElement<Div>> = colors.into_iter()
-            .map(|c| match c {
-                CubeColor::White => Some("cube-facelet-u"),
-                CubeColor::Yellow => Some("cube-facelet-d"),
-                CubeColor::Green => Some("cube-facelet-f"),
-                CubeColor::Blue => Some("cube-facelet-b"),
-                CubeColor::Orange => Some("cube-facelet-l"),
-                CubeColor::Red => Some("cube-facelet-r"),
-                CubeColor::None => None,
-            })
+            .map(|c| c.map(|c| match c {
+                CubeColor::White => "cube-facelet-u",
+                CubeColor::Yellow => "cube-facelet-d",
+                CubeColor::Green => "cube-facelet-f",
+                CubeColor::Blue => "cube-facelet-b",
+                CubeColor::Orange => "cube-facelet-l",
+                CubeColor::Red => "cube-facelet-r",
+                CubeColor::None => "cube-facelet-masked",
+            }))
             .map(|class| class.map_or(
                 view! {<div class="cube-facelet"></div> },
                 |c| view! {<div class={format!("cube-facelet {c}")}></div> }))
@@ -85,4 +102,4 @@ pub fn Cube(cube: Signal<Cube333>) -> impl IntoView {
             {move || facelets.get()}
         </div>
     }
-}
\ No newline at end of file
+}
@@ -23,7 +23,7 @@ pub mod backend {
     use cubelib::algs::Algorithm;
     use cubelib::puzzles::c333::Turn333;
     use cubelib::solver::solution::Solution;
-    use cubelib_interface::{SolverRequest, SolverResponse};
+    use cubelib_interface::{PROTOCOL_VERSION, SolverRequest, SolverResponse, SolverUpdate};
     use ehttp::Request;
     use leptonic::prelude::*;
     use leptos::*;
@@ -38,6 +38,7 @@ pub mod backend {
     enum SolutionState {
         NotFetched,
         Requested,
+        Queued(usize),
         Found(ehttp::Result<Solution<Turn333>>)
     }
 
@@ -107,6 +108,9 @@ pub mod backend {
                     SolutionState::Requested => view! {
                         <Code>"Fetching solution..."</Code>
                     },
+                    SolutionState::Queued(position) => view! {
+                        <Code>{format!("Waiting for a free solver. Position {position} in the queue")}</Code>
+                    },
                     SolutionState::NotFetched => view! {
                         <Code>"Please enter a scramble"</Code>
                     }
@@ -139,11 +143,17 @@ pub mod backend {
 
             match part {
                 ehttp::streaming::Part::Response(response) => {
-                    if response.ok {
-                        std::ops::ControlFlow::Continue(())
-                    } else {
-                        std::ops::ControlFlow::Break(())
+                    //Failed requests usually have an error in the body, which replaces this message once it is read
+                    if !response.ok && cur_id.get_untracked() == id {
+                        let err = if response.status == 503 {
+                            "The server is busy, please try again in a few seconds".to_string()
+                        } else {
+                            format!("{} {}", response.status, response.status_text)
+                        };
+                        solution_callback.set(SolutionState::Found(Err(err)));
+                        done_callback.set(true);
                     }
+                    std::ops::ControlFlow::Continue(())
                 }
                 ehttp::streaming::Part::Chunk(chunk) => {
                     if cur_id.get_untracked() != id {
@@ -155,13 +165,22 @@ pub mod backend {
                             let mut val = current_bytes.take();
                             val.extend_from_slice(&chunk[start..n]);
                             match serde_json::from_slice::<SolverResponse>(val.as_slice()) {
-                                Ok(res) => {
-                                    if let Some(sol) = res.solution {
-                                        solution_callback.set(SolutionState::Found(Ok(sol)))
-                                    }
-                                    if res.done {
-                                        done_callback.set(true);
-                                    }
+                                Ok(res) if res.version != PROTOCOL_VERSION => {
+                                    solution_callback.set(SolutionState::Found(Err(format!("The server uses protocol version {}, expected {PROTOCOL_VERSION}", res.version))));
+                                    done_callback.set(true);
+                                    return std::ops::ControlFlow::Break(());
+                                },
+                                Ok(res) => match res.update {
+                                    SolverUpdate::Solution { solution } => solution_callback.set(SolutionState::Found(Ok(solution))),
+                                    SolverUpdate::Progress { queue_position: Some(position), .. } => solution_callback.set(SolutionState::Queued(position)),
+                                    //The solve left the queue
+                                    SolverUpdate::Progress { queue_position: None, .. } => {
+                                        if let SolutionState::Queued(_) = solution_callback.get_untracked() {
+                                            solution_callback.set(SolutionState::Requested);
+                                        }
+                                    },
+                                    SolverUpdate::Error { error } => solution_callback.set(SolutionState::Found(Err(error.to_string()))),
+                                    SolverUpdate::Done { .. } => done_callback.set(true),
                                 },
                                 Err(err) => {
                                     solution_callback.set(SolutionState::Found(Err(err.to_string())));
@@ -1,6 +1,12 @@
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
-use cubelib::puzzles::c333::Turn333;
+use cubelib::algs::Algorithm;
+use cubelib::puzzles::c333::{Cube333, Turn333};
+use cubelib::puzzles::c333::render;
+use cubelib::puzzles::c333::steps::analysis::Analysis333;
+use cubelib::puzzles::c333::steps::verify::Verification;
+use cubelib::puzzles::puzzle::ApplyAlgorithm;
 use cubelib::solver::solution::Solution;
 use cubelib::steps::step::StepConfig;
 use serde::{Deserialize, Serialize};
@@ -11,8 +17,238 @@ pub struct SolverRequest {
     pub steps: Vec<StepConfig>,
 }
 
+//Sent with every response of /solve_stream and every event of /solve_socket, so clients can tell if they understand the server.
+//Version 1 responses had no version and only a solution and a done flag
+pub const PROTOCOL_VERSION: u32 = 2;
+
+//One line of the /solve_stream response
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
 pub struct SolverResponse {
-    pub solution: Option<Solution<Turn333>>,
-    pub done: bool,
+    pub version: u32,
+    #[serde(flatten)]
+    pub update: SolverUpdate,
+}
+
+impl SolverResponse {
+    pub fn new(update: SolverUpdate) -> SolverResponse {
+        SolverResponse { version: PROTOCOL_VERSION, update }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SolverUpdate {
+    //Every solution is shorter than the previous one
+    Solution {
+        solution: Solution<Turn333>,
+    },
+    //Sent when the queue position changes and when the solver starts searching with a higher quality
+    Progress {
+        //Set while the request waits for a free solver, starting at 1
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        queue_position: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quality: Option<usize>,
+        elapsed_ms: u64,
+    },
+    Error {
+        error: SolverError,
+    },
+    //The last update of every solve. Cancelled is only set on the socket, if the client cancelled the solve
+    Done {
+        #[serde(default)]
+        cancelled: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SolverError {
+    InvalidScramble {
+        scramble: String,
+    },
+    InvalidStepConfig {
+        message: String,
+    },
+    //All solvers are busy and the queue is full
+    Overloaded {
+        retry_after_secs: u64,
+    },
+    //The time ran out before any solution was found
+    Timeout,
+    //A socket message that could not be parsed or does not match the running solves
+    InvalidRequest {
+        message: String,
+    },
+    Internal,
+}
+
+impl Display for SolverError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolverError::InvalidScramble { scramble } => write!(f, "Invalid scramble {scramble}"),
+            SolverError::InvalidStepConfig { message } => write!(f, "Invalid step configuration: {message}"),
+            SolverError::Overloaded { retry_after_secs } => write!(f, "The server is busy, please try again in {retry_after_secs} seconds"),
+            SolverError::Timeout => write!(f, "No solution found in time"),
+            SolverError::InvalidRequest { message } => write!(f, "Invalid request: {message}"),
+            SolverError::Internal => write!(f, "Internal server error"),
+        }
+    }
+}
+
+//Messages from the client on the /solve_socket WebSocket. The client picks the id of every solve,
+//so several solves can run on one connection
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SolverSocketRequest {
+    Solve {
+        id: u64,
+        request: SolverRequest,
+        //How long the solver may search, counted from when the solve leaves the queue. Capped by the server
+        #[serde(default)]
+        time_budget_ms: Option<u64>,
+    },
+    Cancel {
+        id: u64,
+    },
+    SetTimeBudget {
+        id: u64,
+        time_budget_ms: u64,
+    },
+}
+
+//The same updates as on /solve_stream, tagged with the id of the solve. Errors for messages that could not be parsed have no id
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct SolverSocketEvent {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub update: SolverUpdate,
+}
+
+impl SolverSocketEvent {
+    pub fn new(id: Option<u64>, update: SolverUpdate) -> SolverSocketEvent {
+        SolverSocketEvent { version: PROTOCOL_VERSION, id, update }
+    }
+}
+
+//Either a scramble or a cube state has to be set. The cube is a facelet string with 54 letters in URFDLB order,
+//e.g. UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB for a solved cube
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct AnalyzeRequest {
+    #[serde(default)]
+    pub scramble: Option<String>,
+    #[serde(default)]
+    pub cube: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct AnalyzeResponse {
+    #[serde(flatten)]
+    pub analysis: Analysis333,
+}
+
+impl AnalyzeRequest {
+    pub fn get_cube(&self) -> Result<Cube333, String> {
+        match (&self.scramble, &self.cube) {
+            (Some(scramble), None) => {
+                let scramble = Algorithm::<Turn333>::from_str(scramble.as_str()).map_err(|_| format!("Invalid scramble {scramble}"))?;
+                let mut cube = Cube333::default();
+                cube.apply_alg(&scramble);
+                Ok(cube)
+            },
+            (None, Some(cube)) => {
+                let facelets = render::parse_facelet_string(cube.as_str()).map_err(|e| format!("Invalid cube. {e}"))?;
+                Cube333::from_facelets(&facelets).map_err(|e| format!("Invalid cube. {e}"))
+            },
+            _ => Err("Expected either a scramble or a cube".to_string()),
+        }
+    }
+}
+
+//The solution can be a single algorithm, with or without NISS, or one `alg // label` step per line
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct VerifyRequest {
+    pub scramble: String,
+    pub solution: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct VerifyResponse {
+    #[serde(flatten)]
+    pub verification: Verification,
+}
+
+//The best solution the server found for a solve request
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct RecordedSolution {
+    pub length: usize,
+    //The step kinds of the request, e.g. "eo > dr > htr > finish"
+    pub pipeline: String,
+    pub solution: Solution<Turn333>,
+    //Time from receiving the request until the solution was sent
+    pub time_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct HistoryQuery {
+    pub scramble: String,
+}
+
+//All requests for a scramble and its inverse, newest first
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct HistoryResponse {
+    pub canonical_scramble_id: String,
+    pub requests: Vec<HistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct HistoryEntry {
+    pub request_id: i64,
+    pub timestamp: u64,
+    pub scramble: String,
+    pub steps: Vec<StepConfig>,
+    pub best: Option<RecordedSolution>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct PageQuery {
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+}
+
+//The shortest solution for a scramble or its inverse over all requests and settings. The solution solves the scramble next to it
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct BestSolution {
+    pub canonical_scramble_id: String,
+    pub scramble: String,
+    #[serde(flatten)]
+    pub best: RecordedSolution,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct StatsResponse {
+    pub total_requests: u64,
+    pub total_solutions: u64,
+    pub most_requested: Vec<ScrambleCount>,
+    pub pipelines: Vec<PipelineStats>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Debug)]
+pub struct ScrambleCount {
+    pub canonical_scramble_id: String,
+    //The most recent request for this scramble or its inverse
+    pub scramble: String,
+    pub requests: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PipelineStats {
+    pub pipeline: String,
+    pub solutions: u64,
+    pub average_length: f64,
+    pub average_time_ms: f64,
 }